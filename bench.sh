@@ -0,0 +1,75 @@
+#!/bin/bash
+# Compares the release timings of days between two revisions. The binaries of both revisions are
+# run alternately from the repository root, so they read the same inputs and share any noise, and
+# the median "Time spent" of every day is reported. Use . as a revision for the working tree.
+#
+#   ./bench.sh [-n runs] <base-rev> <head-rev> <day>...
+#   ./bench.sh HEAD~1 . 6 10 11
+
+set -euo pipefail
+
+runs=41
+if [[ ${1-} == -n ]]; then
+    runs=$2
+    shift 2
+fi
+if (($# < 3)); then
+    echo "Usage: $0 [-n runs] <base-rev> <head-rev> <day>..." >&2
+    exit 1
+fi
+base=$1
+head=$2
+shift 2
+days=("$@")
+
+root=$(git rev-parse --show-toplevel)
+tmp=$(mktemp -d)
+cleanup() {
+    for side in base head; do
+        [[ -d $tmp/$side ]] && git -C "$root" worktree remove --force "$tmp/$side"
+    done
+    rm -rf "$tmp"
+}
+trap cleanup EXIT
+
+build() {
+    local rev=$1 side=$2 src=$root
+    if [[ $rev != . ]]; then
+        src=$tmp/$side
+        git -C "$root" worktree add -q --detach "$src" "$rev"
+        [[ -f $root/Cargo.lock ]] && cp "$root/Cargo.lock" "$src/"
+    fi
+    for d in "${days[@]}"; do
+        cargo build -q --release --manifest-path "$src/Cargo.toml" --target-dir "$tmp/target-$side" -p "day$d"
+        cp "$tmp/target-$side/release/day$d" "$tmp/$side-day$d"
+    done
+}
+
+# Prints the time one run took in microseconds
+run() {
+    (cd "$root" && "$1" 2>&1) | awk '
+        /Time spent:/ {
+            v = $3; u = v; sub(/[^0-9.]+$/, "", v); sub(/^[0-9.]+/, "", u)
+            print v * (u == "ns" ? 0.001 : u == "ms" ? 1000 : u == "s" ? 1000000 : 1)
+        }'
+}
+
+median() {
+    sort -g | awk '{ v[NR] = $1 } END { print (NR % 2 ? v[(NR + 1) / 2] : (v[NR / 2] + v[NR / 2 + 1]) / 2) }'
+}
+
+build "$base" base
+build "$head" head
+
+for d in "${days[@]}"; do
+    : >"$tmp/base.txt"
+    : >"$tmp/head.txt"
+    for ((i = 0; i < runs; i++)); do
+        run "$tmp/base-day$d" >>"$tmp/base.txt"
+        run "$tmp/head-day$d" >>"$tmp/head.txt"
+    done
+    b=$(median <"$tmp/base.txt")
+    h=$(median <"$tmp/head.txt")
+    awk -v d="$d" -v b="$b" -v h="$h" \
+        'BEGIN { printf "day%s: %.0fµs -> %.0fµs (%+.1f%%)\n", d, b, h, (h - b) / b * 100 }'
+done
@@ -1,15 +1,13 @@
-use std::collections::{HashSet, VecDeque};
+use std::collections::VecDeque;
 use util::*;
 
-const DIRS: [Coord<i32>; 4] = [coord(1, 0), coord(0, 1), coord(-1, 0), coord(0, -1)];
-
 #[aoc_day]
 fn solve(input: Input) -> impl AocResult {
-    let field = BorderedFieldView::new(FieldView::from(&input), b' ');
+    let field = PaddedGrid::new(&FieldView::from(&input), 1, b' ');
 
     let mut total1 = 0;
 
-    let mut done = HashSet::with_capacity(1000);
+    let mut done = FastHashSet::with_capacity_and_hasher(1000, Default::default());
     let mut queue = Vec::with_capacity(1000);
 
     for (offset, _) in field.data().iter().enumerate().filter(|(_, &c)| c == b'0') {
@@ -18,7 +16,7 @@ fn solve(input: Input) -> impl AocResult {
 
         while let Some(coord) = queue.pop() {
             let c = field[coord] + 1;
-            for d in DIRS {
+            for d in Dir4::ALL {
                 let next = coord + d;
                 if field[next] == c && done.insert(next) {
                     if c == b'9' {
@@ -55,7 +53,7 @@ fn solve(input: Input) -> impl AocResult {
         };
 
         let c = c - 1;
-        for d in DIRS {
+        for d in Dir4::ALL {
             let next = coord + d;
             if field[next] == c {
                 reachable[off(next)] += num;
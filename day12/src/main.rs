@@ -9,48 +9,10 @@ struct Region {
     sides: i32,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
-enum RegionRef {
-    Value(Region),
-    Ref(usize),
-}
-
-impl RegionRef {
-    fn as_value(&self) -> Option<&Region> {
-        match self {
-            RegionRef::Value(region) => Some(region),
-            _ => None,
-        }
-    }
-
-    fn as_value_mut(&mut self) -> Option<&mut Region> {
-        match self {
-            RegionRef::Value(region) => Some(region),
-            _ => None,
-        }
-    }
-
-    fn as_ref(&self) -> Option<usize> {
-        match self {
-            RegionRef::Ref(i) => Some(*i),
-            _ => None,
-        }
-    }
-}
-
-fn resolve_offset(regions: &Vec<RegionRef>, mut offset: usize) -> usize {
-    loop {
-        match &regions[offset] {
-            RegionRef::Value(_) => break offset,
-            &RegionRef::Ref(i) => offset = i,
-        }
-    }
-}
-
 #[aoc_day]
 fn solve(input: Input) -> impl AocResult {
     let field = FieldView::from(&input);
-    let mut regions = Vec::with_capacity(1000);
+    let mut regions = DisjointSet::with_capacity(1000);
     let mut regids = vec![0; field.data().len()];
 
     const NEIGHBORS: [Coord<i32>; 4] = [coord(-1, -1), coord(0, -1), coord(1, -1), coord(-1, 0)];
@@ -126,8 +88,8 @@ fn solve(input: Input) -> impl AocResult {
         const PERIM_DELTA: [i32; 16] = [0, 0, 2, 2, 0, 0, 2, 2, 2, 2, 0, 0, 2, 2, 0, 0];
         const SIDE_DELTA: [i32; 16] = [0, 0, 0, 2, 0, 0, 2, 4, 0, 2, -2, -2, 0, 2, 0, 0];
 
-        for i in 0..4 {
-            let n = coord + NEIGHBORS[i];
+        for (i, &d) in NEIGHBORS.iter().enumerate() {
+            let n = coord + d;
             if c == *field.get_or(n.x, n.y, &b' ') {
                 mask |= 1 << i;
             }
@@ -136,38 +98,32 @@ fn solve(input: Input) -> impl AocResult {
         // print!("{} at {coord} [mask {mask}]: ", to_str(std::slice::from_ref(&c)));
 
         if mask & 8 == 8 {
-            ids[numids.post_inc()] = resolve_offset(&regions, regids[offset - 1]);
+            ids[numids.post_inc()] = regions.find(regids[offset - 1]);
         }
 
         if mask & 2 == 2 {
-            ids[numids.post_inc()] =
-                resolve_offset(&regions, regids[offset - field.stride() as usize]);
+            ids[numids.post_inc()] = regions.find(regids[offset - field.stride()]);
         }
 
         match mask {
             0 | 1 | 4 | 5 => {
-                regids[offset] = regions.len();
-                regions.push(RegionRef::Value(Region {
+                regids[offset] = regions.push(Region {
                     area: 1,
                     perimeter: 4,
                     sides: 4,
-                }));
+                });
                 // println!("new region");
             }
             10 | 14 if ids[0] != ids[1] => {
-                let reg0 = regions[ids[0]].as_value().unwrap().clone();
-                let reg1 = regions[ids[1]].as_value_mut().unwrap();
-                // print!("merge {reg0:?} + {reg1:?}");
-                reg1.area += reg0.area + 1;
-                reg1.perimeter += reg0.perimeter + PERIM_DELTA[mask as usize];
-                reg1.sides += reg0.sides + SIDE_DELTA[mask as usize];
-                // println!(" => {reg1:?}");
-                regions[ids[0]] = RegionRef::Ref(ids[1]);
-                regids[offset] = ids[1];
+                regids[offset] = regions.union_with(ids[0], ids[1], |reg0, reg1| Region {
+                    area: reg0.area + reg1.area + 1,
+                    perimeter: reg0.perimeter + reg1.perimeter + PERIM_DELTA[mask as usize],
+                    sides: reg0.sides + reg1.sides + SIDE_DELTA[mask as usize],
+                });
             }
             16.. => unreachable!(),
             _ => {
-                let reg = regions[ids[0]].as_value_mut().unwrap();
+                let reg = regions.get_mut(ids[0]);
                 reg.area += 1;
                 reg.perimeter += PERIM_DELTA[mask as usize];
                 reg.sides += SIDE_DELTA[mask as usize];
@@ -180,18 +136,12 @@ fn solve(input: Input) -> impl AocResult {
     // println!("{}", regions.len());
 
     let total1 = regions
-        .iter()
-        .filter_map(|r| match r {
-            RegionRef::Value(region) => Some(region.area as u64 * region.perimeter as u64),
-            _ => None,
-        })
+        .sets()
+        .map(|(_, region)| region.area as u64 * region.perimeter as u64)
         .sum::<u64>();
     let total2 = regions
-        .iter()
-        .filter_map(|r| match r {
-            RegionRef::Value(region) => Some(region.area as u64 * region.sides as u64),
-            _ => None,
-        })
+        .sets()
+        .map(|(_, region)| region.area as u64 * region.sides as u64)
         .sum::<u64>();
 
     (total1, total2)
@@ -8,61 +8,36 @@ fn solve(input: Input) -> impl AocResult {
 
     let mut total1 = 0;
     let mut total2 = 0;
-    loop {
-        let Some(a) = lines.next() else {
-            break;
-        };
+    while let Some(a) = lines.next() {
         let b = lines.next().unwrap();
         let prize = lines.next().unwrap();
 
         let mut a = Parser::new(a);
         let a = coord(
-            a.expect("Button A: X+").parse::<i32>().unwrap(),
-            a.expect(", Y+").parse::<i32>().unwrap(),
+            a.expect("Button A: X+").parse::<i64>().unwrap(),
+            a.expect(", Y+").parse::<i64>().unwrap(),
         );
 
         let mut b = Parser::new(b);
         let b = coord(
-            b.expect("Button B: X+").parse::<i32>().unwrap(),
-            b.expect(", Y+").parse::<i32>().unwrap(),
+            b.expect("Button B: X+").parse::<i64>().unwrap(),
+            b.expect(", Y+").parse::<i64>().unwrap(),
         );
 
         let mut prize = Parser::new(prize);
         let prize = coord(
-            prize.expect("Prize: X=").parse::<i32>().unwrap(),
-            prize.expect(", Y=").parse::<i32>().unwrap(),
+            prize.expect("Prize: X=").parse::<i64>().unwrap(),
+            prize.expect(", Y=").parse::<i64>().unwrap(),
         );
 
-        /*
-           Matrix = |a.x b.x|
-                    |a.y b.y|
-
-           inverse= |b.y -b.x|  /
-                    |-a.y a.x| /  det
-
-           result = (|b.y -a.y| * prize.x + |-b.x a.x| * prize.y) / det
-        */
-
-        let det = (a.x * b.y) - (a.y * b.x);
-        let invx = coord(b.y, -a.y);
-        let invy = coord(-b.x, a.x);
-        let mut result = invx * prize.x + invy * prize.y;
-        if result % det == coord(0, 0) {
-            result /= det;
-            let cost = 3 * result.x + result.y;
-            total1 += cost;
-        }
-
-        let det = det as i64;
-        let prize = prize.to::<i64>() + coord(10000000000000, 10000000000000);
-        let invx = invx.to::<i64>();
-        let invy = invy.to::<i64>();
-        let mut result = invx * prize.x + invy * prize.y;
-        if result % det == coord(0, 0) {
-            result /= det;
-            let cost = 3 * result.x + result.y;
-            total2 += cost;
-        }
+        // Buttons whose movements are collinear leave a choice between many combinations, which
+        // solve2_nonneg_integer resolves by picking the cheapest one
+        let tokens = |prize: Coord<i64>| {
+            linalg::solve2_nonneg_integer([[a.x, b.x], [a.y, b.y]], [prize.x, prize.y], [3, 1])
+                .map_or(0, |[na, nb]| 3 * na + nb)
+        };
+        total1 += tokens(prize);
+        total2 += tokens(prize + coord(10000000000000, 10000000000000));
 
         lines.next();
     }
@@ -3,16 +3,26 @@
 use util::*;
 
 #[aoc_day]
-fn solve(input: Input) -> impl AocResult {
+fn solve(input: Input) -> std::io::Result<(i32, i32)> {
     let size = match input.is_example() {
         false => coord(101, 103),
         true => coord(11, 7),
     };
+    // Both sizes are odd, so the middle row and column belong to no quadrant
     let mid = size / 2;
+    let count_quadrants = |robots: &[(Coord<i32>, Coord<i32>)], t: i32| {
+        let mut counts = [0; 4];
+        for &(pos, v) in robots {
+            let pos = (pos + v * t).rem_euclid(size);
+            if pos.x != mid.x && pos.y != mid.y {
+                counts[(pos.x > mid.x) as usize + 2 * (pos.y > mid.y) as usize] += 1;
+            }
+        }
+        counts
+    };
 
     const SECONDS: i32 = 100;
 
-    let mut quadrants = [0; 4];
     let mut robots = Vec::with_capacity(500);
     for l in input.lines() {
         let mut p = Parser::new(l);
@@ -25,34 +35,28 @@ fn solve(input: Input) -> impl AocResult {
             p.expect(",").parse::<i32>().unwrap(),
         );
         robots.push((pos, v));
-
-        let new_pos = pos + v * SECONDS;
-        let new_pos = coord(new_pos.x.rem_euclid(size.x), new_pos.y.rem_euclid(size.y));
-        match new_pos.tuple() {
-            (x, y) if x < mid.x && y < mid.y => quadrants[0] += 1,
-            (x, y) if x > mid.x && y < mid.y => quadrants[1] += 1,
-            (x, y) if x < mid.x && y > mid.y => quadrants[2] += 1,
-            (x, y) if x > mid.x && y > mid.y => quadrants[3] += 1,
-            _ => {}
-        }
     }
+
+    let quadrants = count_quadrants(&robots, SECONDS);
     let total1 = quadrants[0] * quadrants[1] * quadrants[2] * quadrants[3];
 
+    let mut recorder = input.recorder()?.map(|r| r.with_scale(4));
+    let robot_image = |t: i32| {
+        let mut img = image::Image::new(size.x as usize, size.y as usize, image::BLACK);
+        for &(pos, v) in &robots {
+            img[(pos + v * t).rem_euclid(size)] = image::WHITE;
+        }
+        img
+    };
+
     let mut minscore = coord(i32::MAX, i32::MAX);
     let mut minpos = coord(0, 0);
     for i in 0..size.x.max(size.y) {
-        let mut quadrants = [0; 4];
-        for r in &robots {
-            let pos = r.0 + r.1 * i;
-            let pos = coord(pos.x.rem_euclid(size.x), pos.y.rem_euclid(size.y));
-            match pos.tuple() {
-                (x, y) if x < mid.x && y < mid.y => quadrants[0] += 1,
-                (x, y) if x > mid.x && y < mid.y => quadrants[1] += 1,
-                (x, y) if x < mid.x && y > mid.y => quadrants[2] += 1,
-                (x, y) if x > mid.x && y > mid.y => quadrants[3] += 1,
-                _ => {}
-            }
+        if let Some(rec) = &mut recorder {
+            rec.record(&robot_image(i))?;
         }
+
+        let quadrants = count_quadrants(&robots, i);
         let score = coord(
             (quadrants[0] + quadrants[2]) * (quadrants[1] + quadrants[3]),
             (quadrants[0] + quadrants[1]) * (quadrants[2] + quadrants[3]),
@@ -67,8 +71,12 @@ fn solve(input: Input) -> impl AocResult {
         }
     }
 
-    let (_, x, y) = extended_euclidian(size.x, size.y);
-    let total2 = (minpos.x * y * size.y + minpos.y * x * size.x) % (size.x * size.y);
+    let (total2, _) = num::crt(&[(minpos.x, size.x), (minpos.y, size.y)]).unwrap();
+
+    if let Some(mut rec) = recorder {
+        rec.record(&robot_image(total2))?;
+        rec.finish()?;
+    }
 
-    (total1, total2)
+    Ok((total1, total2))
 }
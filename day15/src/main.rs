@@ -5,25 +5,18 @@ use itertools::*;
 use util::*;
 
 #[aoc_day]
-fn solve(input: Input) -> impl AocResult {
+fn solve(input: Input) -> std::io::Result<(u64, u64)> {
     let (field_in, code_in) = input.paragraphs().collect_tuple().unwrap();
     let mut field_vec = Vec::from(field_in.as_bytes());
     let mut field = FieldMutView::from(field_vec.as_mut_slice());
 
-    let robot_start =
-        field.coord_from_offset::<i32>(field.data().iter().position(|&c| c == b'@').unwrap());
+    let robot_start = field.find_unique::<i32>(b'@').unwrap();
     let mut robot = robot_start;
     field[robot] = b'.';
 
     for &c in code_in.as_bytes() {
-        let dir = match c {
-            b'^' => coord(0, -1),
-            b'v' => coord(0, 1),
-            b'<' => coord(-1, 0),
-            b'>' => coord(1, 0),
-            _ => {
-                continue;
-            }
+        let Some(dir) = Dir4::from_arrow(c).map(|d| d.to_coord::<i32>()) else {
+            continue;
         };
 
         let mut pos = robot + dir;
@@ -72,33 +65,22 @@ fn solve(input: Input) -> impl AocResult {
     let mut field = FieldMutView::from(field_vec.as_mut_slice());
 
     let mut robot = coord(robot_start.x * 2, robot_start.y);
-    let mut checked_vec = vec![false; field.width() * field.height()];
-    let mut checked = FieldMutView::from_ref(
-        &mut checked_vec,
-        field.width(),
-        field.width(),
-        field.height(),
-    );
+    let mut checked = BitField::new(field.width(), field.height());
     let mut queue = Vec::with_capacity(1000);
+    let mut vis = input.visualizer();
+    let mut recorder = input.recorder()?.map(|r| r.with_scale(4));
+    let palette = image::Palette::default();
     'outer: for &c in code_in.as_bytes() {
-        let dir = match c {
-            b'^' => coord(0, -1),
-            b'v' => coord(0, 1),
-            b'<' => coord(-1, 0),
-            b'>' => coord(1, 0),
-            _ => {
-                continue;
-            }
+        let Some(dir) = Dir4::from_arrow(c).map(|d| d.to_coord::<i32>()) else {
+            continue;
         };
 
-        // field[robot] = b'@';
-        // println!(
-        //     "move {}\n{}",
-        //     to_str(slice::from_ref(&c)),
-        //     to_str(field.data())
-        // );
-        // std::io::stdin().read_line(&mut String::new()).unwrap();
-        // field[robot] = b'.';
+        vis.show(|| format!("move {}\n{}", c as char, field.render().mark([robot], '@')));
+        if let Some(rec) = &mut recorder {
+            let mut img = image::Image::from_bytes(&field, &palette);
+            img[robot] = palette.get(b'@');
+            rec.record(&img)?;
+        }
 
         let mut pos = robot + dir;
         if dir.y == 0 {
@@ -130,9 +112,7 @@ fn solve(input: Input) -> impl AocResult {
             }
         } else {
             'ok: {
-                for &p in &queue {
-                    checked[p] = false;
-                }
+                checked.fill(false);
                 queue.clear();
                 match field[pos] {
                     b'#' => {
@@ -165,11 +145,11 @@ fn solve(input: Input) -> impl AocResult {
                         (c0, c1) => {
                             if c0 == b'[' {
                                 queue.push(pos);
-                            } else if c0 == b']' && !checked[pos.left()].exchange(true) {
+                            } else if c0 == b']' && checked.insert(pos.left()) {
                                 queue.push(pos.left());
                             }
 
-                            if c1 == b'[' && !checked[pos.right()].exchange(true) {
+                            if c1 == b'[' && checked.insert(pos.right()) {
                                 queue.push(pos.right());
                             }
                         }
@@ -189,6 +169,10 @@ fn solve(input: Input) -> impl AocResult {
         robot += dir;
     }
 
+    if let Some(rec) = recorder {
+        rec.finish()?;
+    }
+
     let total2 = field
         .data()
         .iter()
@@ -200,5 +184,5 @@ fn solve(input: Input) -> impl AocResult {
         })
         .sum::<u64>();
 
-    (total1, total2)
+    Ok((total1, total2))
 }
@@ -35,8 +35,7 @@ fn solve(input: Input) -> impl AocResult {
         size.x as usize,
         size.y as usize,
     );
-    let mut visited =
-        FieldMutView::create_with_value(false, size.x as usize, size.x as usize, size.y as usize);
+    let mut visited = BitField::new(size.x as usize, size.y as usize);
 
     for (step, &c) in coords.iter().enumerate() {
         field[c] = step as u16 + 1;
@@ -44,7 +43,7 @@ fn solve(input: Input) -> impl AocResult {
 
     let mut queue = VecDeque::with_capacity(1000);
     queue.push_back((coord(0, 0), 0));
-    visited[0] = true;
+    visited.insert(coord(0, 0));
 
     let total1 = 'done: {
         while let Some((pos, cost)) = queue.pop_front() {
@@ -52,8 +51,7 @@ fn solve(input: Input) -> impl AocResult {
                 if n == end_pos {
                     break 'done cost + 1;
                 }
-                if field.in_bounds_coord(n) && field[n] > part1_steps as u16 && !visited[n] {
-                    visited[n] = true;
+                if field.in_bounds_coord(n) && field[n] > part1_steps as u16 && visited.insert(n) {
                     queue.push_back((n, cost + 1));
                 }
             }
@@ -71,9 +69,9 @@ fn solve(input: Input) -> impl AocResult {
             }
 
             queue.clear();
-            visited.data_mut().fill(false);
+            visited.fill(false);
             queue.push_back(coord(0, 0));
-            visited[0] = true;
+            visited.insert(coord(0, 0));
             let mid = (max + min) / 2;
             while let Some(pos) = queue.pop_front() {
                 for n in pos.neighbors4() {
@@ -81,8 +79,7 @@ fn solve(input: Input) -> impl AocResult {
                         min = mid;
                         continue 'next;
                     }
-                    if field.in_bounds_coord(n) && field[n] > mid as u16 && !visited[n] {
-                        visited[n] = true;
+                    if field.in_bounds_coord(n) && field[n] > mid as u16 && visited.insert(n) {
                         queue.push_back(n);
                     }
                 }
@@ -3,7 +3,6 @@ use util::*;
 
 #[aoc_day]
 fn solve(input: Input) -> impl AocResult {
-
     let mut total1 = 0;
     let mut total2 = 0;
 
@@ -2,43 +2,36 @@
 
 use util::*;
 
+#[derive(Cell, Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    #[cell('#')]
+    Wall,
+    #[cell('.')]
+    Track,
+    #[cell('S')]
+    Start,
+    #[cell('E')]
+    End,
+}
+
 #[aoc_day]
 fn solve(input: Input) -> impl AocResult {
-    let field = FieldView::from(&input);
-
-    let start =
-        field.coord_from_offset::<i32>(field.data().iter().position(|&c| c == b'S').unwrap());
-    let end = field.coord_from_offset::<i32>(field.data().iter().position(|&c| c == b'E').unwrap());
-    let mut path = Vec::with_capacity(10000);
-    let mut lengths =
-        FieldMutView::create_with_value(-1, field.width(), field.width(), field.height());
+    let field = FieldView::from(&input).map_cells::<Tile>().unwrap();
 
-    let mut pos = start;
-    let mut dir = coord(1, 0);
-    let mut total_len = 0;
-    'path: while pos != end {
-        path.push(pos);
-        lengths[pos] = total_len;
-        total_len += 1;
-
-        for d in [dir, dir.turn_left(), dir.turn_right(), -dir] {
-            let next = pos + d;
-            if field[next] != b'#' {
-                pos = next;
-                dir = d;
-                continue 'path;
-            }
-        }
-        unreachable!();
-    }
-    lengths[end] = total_len;
+    let start = field.find_unique::<i32>(Tile::Start).unwrap();
+    let lengths = field.bfs_distances([start], |&t| t != Tile::Wall);
+    let path: Vec<Coord<i32>> = lengths
+        .coords()
+        .filter(|&p| lengths[p] != UNREACHABLE)
+        .collect();
+    // Without short circuiting, and counted in u32, so the row scans below compile to tight loops
+    let saves = |from: u32, to: u32, cost: u32| (to != UNREACHABLE) & (to >= from + cost + 100);
 
-    let mut total1 = 0;
-    let mut total2 = 0;
-    for &p in &path {
+    let count = |&p: &Coord<i32>| {
+        let mut total1 = 0_u32;
         let start_cut = lengths[p];
         for n in p.neighbors4() {
-            if field[n] != b'#' {
+            if field[n] != Tile::Wall {
                 continue;
             }
 
@@ -47,22 +40,31 @@ fn solve(input: Input) -> impl AocResult {
                 continue;
             }
 
-            if lengths[n2] - start_cut - 2 >= 100 {
+            if saves(start_cut, lengths[n2], 2) {
                 total1 += 1;
             }
         }
 
-        for y in 0.max(p.y - 20)..(field.height() as i32).min(p.y + 21) {
-            let y_cost = y.abs_diff(p.y) as i32;
-            let x_count = 20 - y_cost as i32;
-            for x in 0.max(p.x - x_count)..(field.width() as i32).min(p.x + x_count + 1) {
-                let cost = x.abs_diff(p.x) as i32 + y_cost;
-                if lengths[coord(x, y)] - start_cut - cost >= 100 {
-                    total2 += 1;
-                }
-            }
-        }
-    }
+        let total2 = lengths
+            .manhattan_rows(p, 20)
+            .map(|(start, row)| {
+                let dy = start.y.abs_diff(p.y);
+                (start.x..)
+                    .zip(row)
+                    .map(|(x, &d)| saves(start_cut, d, x.abs_diff(p.x) + dy) as u32)
+                    .sum::<u32>()
+            })
+            .sum::<u32>();
+        (total1, total2)
+    };
+
+    #[cfg(feature = "parallel")]
+    let counts = ScopedPool::default().map(&path, count);
+    #[cfg(not(feature = "parallel"))]
+    let counts: Vec<_> = path.iter().map(count).collect();
+
+    let total1 = counts.iter().map(|c| c.0).sum::<u32>();
+    let total2 = counts.iter().map(|c| c.1).sum::<u32>();
 
     (total1, total2)
 }
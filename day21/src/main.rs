@@ -1,11 +1,12 @@
 #![allow(dead_code)]
 
+use util::memo::{DenseBackend, Memo};
 use util::*;
 
-const BUTTON_UP: u8 = 0;
-const BUTTON_DOWN: u8 = 1;
-const BUTTON_LEFT: u8 = 2;
-const BUTTON_RIGHT: u8 = 3;
+const BUTTON_UP: u8 = Dir4::Up as u8;
+const BUTTON_DOWN: u8 = Dir4::Down as u8;
+const BUTTON_LEFT: u8 = Dir4::Left as u8;
+const BUTTON_RIGHT: u8 = Dir4::Right as u8;
 const BUTTON_A: u8 = 10;
 
 const NUMBER_PAD: [Coord<i32>; 11] = [
@@ -24,9 +25,9 @@ const NUMBER_PAD: [Coord<i32>; 11] = [
 
 const DIRECTION_PAD: [Coord<i32>; 11] = [
     coord(1, 0), // Up
+    coord(2, 1), // Right
     coord(1, 1), // Down
     coord(0, 1), // Left
-    coord(2, 1), // Right
     coord(0, 0),
     coord(0, 0),
     coord(0, 0),
@@ -36,77 +37,76 @@ const DIRECTION_PAD: [Coord<i32>; 11] = [
     coord(2, 0), // A
 ];
 
-const DIRECTION_CHARS: &[u8; 11] = b"^v<>      A";
-
-type StateLut = [[u64; 11]; 11];
+// Keyed by (depth, prev, key), where depth counts the pads left including this one
+type StateLut = Memo<usize, u64, DenseBackend<u64>>;
 
-fn press_number(key: u8, prev: &mut u8, lut: &mut [StateLut]) -> u64 {
-    press(key, prev, &NUMBER_PAD, lut)
+fn state_lut(depth: usize) -> StateLut {
+    Memo::dense((depth + 1) * 11 * 11)
 }
 
-fn press_direction(key: u8, prev: &mut u8, lut: &mut [StateLut]) -> u64 {
-    press(key, prev, &DIRECTION_PAD, lut)
+fn press_number(key: u8, prev: &mut u8, depth: usize, lut: &mut StateLut) -> u64 {
+    press(key, prev, &NUMBER_PAD, depth, lut)
 }
 
-fn press(key: u8, prev: &mut u8, pad: &[Coord<i32>], lut: &mut [StateLut]) -> u64 {
-    let (this_lut, lut) = lut.split_first_mut().unwrap();
+fn press_direction(key: u8, prev: &mut u8, depth: usize, lut: &mut StateLut) -> u64 {
+    press(key, prev, &DIRECTION_PAD, depth, lut)
+}
 
-    if this_lut[*prev as usize][key as usize] > 0 {
-        let total = this_lut[*prev as usize][key as usize];
-        *prev = key;
-        return total;
-    }
+fn press(key: u8, prev: &mut u8, pad: &[Coord<i32>], depth: usize, lut: &mut StateLut) -> u64 {
+    let state = (depth * 11 + *prev as usize) * 11 + key as usize;
+    let from = *prev;
+    *prev = key;
+    lut.get_or_insert_with(state, |lut| press_uncached(key, from, pad, depth, lut))
+}
 
+fn press_uncached(key: u8, prev: u8, pad: &[Coord<i32>], depth: usize, lut: &mut StateLut) -> u64 {
     let mut total = 0;
     let a_pos = pad[BUTTON_A as usize];
-    let pos = pad[*prev as usize];
+    let pos = pad[prev as usize];
     let new_pos = pad[key as usize];
     let diff = new_pos - pos;
 
-    if lut.is_empty() {
-        let total = (diff.x.abs() + diff.y.abs() + 1) as u64;
-        this_lut[*prev as usize][key as usize] = total;
-        *prev = key;
-        return total;
+    if depth == 1 {
+        return (diff.x.abs() + diff.y.abs() + 1) as u64;
     }
 
+    let depth = depth - 1;
+
     let mut cur = BUTTON_A;
 
     if diff.x < 0 && !(new_pos.x == 0 && pos.y == a_pos.y) {
         for _ in 0..-diff.x {
-            total += press_direction(BUTTON_LEFT, &mut cur, lut);
+            total += press_direction(BUTTON_LEFT, &mut cur, depth, lut);
         }
     }
     if diff.x > 0 && (pos.x == 0 && new_pos.y == a_pos.y) {
         for _ in 0..diff.x {
-            total += press_direction(BUTTON_RIGHT, &mut cur, lut);
+            total += press_direction(BUTTON_RIGHT, &mut cur, depth, lut);
         }
     }
 
     if diff.y > 0 {
         for _ in 0..diff.y {
-            total += press_direction(BUTTON_DOWN, &mut cur, lut);
+            total += press_direction(BUTTON_DOWN, &mut cur, depth, lut);
         }
     } else if diff.y < 0 {
         for _ in 0..-diff.y {
-            total += press_direction(BUTTON_UP, &mut cur, lut);
+            total += press_direction(BUTTON_UP, &mut cur, depth, lut);
         }
     }
 
     if diff.x > 0 && !(pos.x == 0 && new_pos.y == a_pos.y) {
         for _ in 0..diff.x {
-            total += press_direction(BUTTON_RIGHT, &mut cur, lut);
+            total += press_direction(BUTTON_RIGHT, &mut cur, depth, lut);
         }
     }
     if diff.x < 0 && (new_pos.x == 0 && pos.y == a_pos.y) {
         for _ in 0..-diff.x {
-            total += press_direction(BUTTON_LEFT, &mut cur, lut);
+            total += press_direction(BUTTON_LEFT, &mut cur, depth, lut);
         }
     }
 
-    total += press_direction(BUTTON_A, &mut cur, lut);
-    this_lut[*prev as usize][key as usize] = total;
-    *prev = key;
+    total += press_direction(BUTTON_A, &mut cur, depth, lut);
 
     total
 }
@@ -114,7 +114,10 @@ fn press(key: u8, prev: &mut u8, pad: &[Coord<i32>], lut: &mut [StateLut]) -> u6
 fn dir_str(seq: &[u8]) -> String {
     let mut s = String::with_capacity(seq.len());
     for &c in seq {
-        s.push(DIRECTION_CHARS[c as usize] as char);
+        s.push(match c {
+            BUTTON_A => 'A',
+            c => Dir4::from_index(c as usize).to_arrow() as char,
+        });
     }
     s
 }
@@ -124,8 +127,10 @@ fn solve(input: Input) -> impl AocResult {
     let mut total1 = 0;
     let mut total2 = 0;
 
-    let mut lut1 = [StateLut::default(); 3];
-    let mut lut2 = [StateLut::default(); 26];
+    const DEPTH1: usize = 3;
+    const DEPTH2: usize = 26;
+    let mut lut1 = state_lut(DEPTH1);
+    let mut lut2 = state_lut(DEPTH2);
 
     for l in input.lines() {
         let b = l.as_bytes();
@@ -139,7 +144,7 @@ fn solve(input: Input) -> impl AocResult {
         let mut len = 0;
         let mut cur = BUTTON_A;
         for &key in &seq {
-            len += press_number(key, &mut cur, &mut lut1);
+            len += press_number(key, &mut cur, DEPTH1, &mut lut1);
         }
         println!("{l}: {len}");
 
@@ -148,7 +153,7 @@ fn solve(input: Input) -> impl AocResult {
         let mut len = 0;
         let mut cur = BUTTON_A;
         for &key in &seq {
-            len += press_number(key, &mut cur, &mut lut2);
+            len += press_number(key, &mut cur, DEPTH2, &mut lut2);
         }
 
         total2 += weight * len;
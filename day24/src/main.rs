@@ -1,6 +1,6 @@
 #![allow(dead_code)]
 
-use std::{collections::HashMap, hash::Hash};
+use std::hash::Hash;
 
 use util::*;
 
@@ -69,7 +69,7 @@ impl Annotation {
 #[aoc_day]
 fn solve(input: Input) -> impl AocResult {
     let mut lines = input.lines();
-    let mut names = HashMap::with_capacity(500);
+    let mut names = FastHashMap::with_capacity_and_hasher(500, Default::default());
     let mut data = vec![0_u8; 64];
 
     for l in lines.by_ref() {
@@ -152,8 +152,8 @@ fn solve(input: Input) -> impl AocResult {
         annotation: Annotation,
         annotations: &mut [Annotation],
         sources: &mut [(usize, usize, Op)],
-        names: &HashMap<&str, usize>,
-        annotated_gates: &mut HashMap<Annotation, usize>,
+        names: &FastHashMap<&str, usize>,
+        annotated_gates: &mut FastHashMap<Annotation, usize>,
         swaps: &mut Vec<(usize, usize)>,
     ) -> usize {
         use Annotation::*;
@@ -224,7 +224,7 @@ fn solve(input: Input) -> impl AocResult {
     }
 
     let mut annotations = vec![Annotation::None; data.len()];
-    let mut annotated_gates = HashMap::with_capacity(data.len());
+    let mut annotated_gates = FastHashMap::with_capacity_and_hasher(data.len(), Default::default());
     let mut swaps = Vec::with_capacity(4);
     for i in 0..=max_z {
         let a = match i {
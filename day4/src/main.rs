@@ -3,82 +3,12 @@ use util::*;
 #[aoc_day]
 fn solve(input: Input) -> impl AocResult {
     let field = FieldView::from(&input);
-    let width = field.width();
-    let height = field.height();
-    let stride = field.stride();
-    let data = field.data();
-    let istride = stride as isize;
 
-    let mut total1 = 0;
-    let mut total2 = 0;
+    let xmas = Pattern::word(b"XMAS", &coord(0, 0).neighbors8());
+    let x_mas = Pattern::template(b"M.S\n.A.\nM.S", b'.', &Transform::ROTATIONS);
 
-    fn check1(data: &[u8], base: usize, delta: isize) -> i32 {
-        let base = base as isize;
-        (data[(base + delta) as usize] == b'M'
-            && data[(base + 2 * delta) as usize] == b'A'
-            && data[(base + 3 * delta) as usize] == b'S') as i32
-    }
-
-    let mut rest = data;
-    let mut base = 0;
-    while !rest.is_empty() {
-        let Some(idx) = rest.iter().position(|&c| c == b'X') else {
-            break;
-        };
-        base += idx;
-
-        let (x, y) = field.tuple_from_offset::<usize>(base);
-        if x >= 3 {
-            total1 += check1(data, base, -1);
-            if y >= 3 {
-                total1 += check1(data, base, -istride - 1);
-            }
-            if y <= height - 4 {
-                total1 += check1(data, base, istride - 1);
-            }
-        }
-        if x <= width - 4 {
-            total1 += check1(data, base, 1);
-            if y >= 3 {
-                total1 += check1(data, base, -istride + 1);
-            }
-            if y <= height - 4 {
-                total1 += check1(data, base, istride + 1);
-            }
-        }
-        if y >= 3 {
-            total1 += check1(data, base, -istride);
-        }
-        if y <= height - 4 {
-            total1 += check1(data, base, istride);
-        }
-
-        rest = &rest[idx + 1..];
-        base += 1;
-    }
-
-    fn check2(data: &[u8], base: usize, stride: usize) -> i32 {
-        let pattern = &[
-            data[base - stride - 1],
-            data[base - stride + 1],
-            data[base + stride - 1],
-            data[base + stride + 1],
-        ];
-        (pattern == b"MMSS" || pattern == b"MSMS" || pattern == b"SSMM" || pattern == b"SMSM")
-            as i32
-    }
-
-    let mut base = stride + 1;
-    let mut rest = &data[base..data.len() - stride - 1];
-    while !rest.is_empty() {
-        let Some(idx) = rest.iter().position(|&c| c == b'A') else {
-            break;
-        };
-        base += idx;
-        total2 += check2(data, base, stride);
-        rest = &rest[idx + 1..];
-        base += 1;
-    }
+    let total1 = xmas.count(&field);
+    let total2 = x_mas.count(&field);
 
     (total1, total2)
 }
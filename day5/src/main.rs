@@ -1,9 +1,11 @@
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use util::graph::{Graph, Interner};
 use util::*;
 
 #[aoc_day]
 fn solve(input: Input) -> impl AocResult {
-    let mut rules: HashMap<i32, Vec<i32>> = HashMap::new();
+    let mut pages = Interner::with_capacity(100);
+    let mut rules = Vec::with_capacity(2000);
     let mut lines = input.lines();
 
     for l in lines.by_ref() {
@@ -12,64 +14,58 @@ fn solve(input: Input) -> impl AocResult {
         }
 
         let mut p = Parser::new(l);
-        let page1 = p.parse().unwrap();
-        let page2 = p.expect("|").parse().unwrap();
-
-        rules.entry(page1).or_default().push(page2);
+        let page1 = pages.intern(p.parse::<i32>().unwrap());
+        let page2 = pages.intern(p.expect("|").parse::<i32>().unwrap());
+        rules.push((page1, page2));
     }
 
+    let updates: Vec<Vec<usize>> = lines
+        .map(|l| {
+            Parser::new(l)
+                .parse_iter::<i32>(",")
+                .map(|p| pages.intern(p))
+                .collect()
+        })
+        .collect();
+    let rules = Graph::from_edges(pages.len(), rules);
+
     let mut total1 = 0;
     let mut total2 = 0;
 
-    let mut pages = Vec::with_capacity(1000);
-    let mut page_indices = HashMap::with_capacity(1000);
-
-    'nextline: for l in lines {
-        pages.clear();
-        pages.extend(Parser::new(l).parse_iter::<i32>(","));
-
-        page_indices.clear();
-
-        for (idx, &p) in pages.iter().enumerate() {
-            page_indices.insert(p, idx);
+    // Scratch space shared by all updates: where every page sits in the current update, and a copy
+    // of it to sort
+    let mut position = vec![usize::MAX; pages.len()];
+    let mut sorted = Vec::new();
+    for update in &updates {
+        for (i, &p) in update.iter().enumerate() {
+            position[p] = i;
         }
-
-        'part1: {
-            for (idx, &p) in pages.iter().enumerate() {
-                let Some(r) = rules.get(&p) else {
-                    continue;
-                };
-
-                for &p2 in r {
-                    if let Some(&idx2) = page_indices.get(&p2) {
-                        if idx2 < idx {
-                            break 'part1;
-                        }
-                    }
-                }
-            }
-
-            total1 += pages[pages.len() / 2];
-            continue 'nextline;
+        let in_order = update.iter().enumerate().all(|(i, &p)| {
+            rules
+                .neighbors(p)
+                .iter()
+                .all(|&q| position[q] == usize::MAX || position[q] > i)
+        });
+        for &p in update {
+            position[p] = usize::MAX;
         }
 
-        let mid = pages.len() / 2;
-        total2 += *pages
-            .select_nth_unstable_by(mid, |&a, &b| {
-                use std::cmp::Ordering::*;
-                if let Some(r) = rules.get(&a) {
-                    if r.contains(&b) {
-                        return Less;
-                    }
+        let mid = update.len() / 2;
+        if in_order {
+            total1 += pages.name(update[mid]);
+        } else {
+            sorted.clone_from(update);
+            let (_, &mut page, _) = sorted.select_nth_unstable_by(mid, |&a, &b| {
+                if rules.has_edge(a, b) {
+                    Ordering::Less
+                } else if rules.has_edge(b, a) {
+                    Ordering::Greater
+                } else {
+                    Ordering::Equal
                 }
-                if let Some(r) = rules.get(&b) {
-                    if r.contains(&a) {
-                        return Greater;
-                    }
-                }
-                Equal
-            })
-            .1;
+            });
+            total2 += pages.name(page);
+        }
     }
 
     (total1, total2)
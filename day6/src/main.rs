@@ -1,59 +1,83 @@
-use std::collections::HashSet;
 use util::*;
 
 #[aoc_day]
 fn solve(input: Input) -> impl AocResult {
-    let field = BorderedFieldView::new(FieldView::from(&input), b' ');
+    let field = PaddedGrid::new(&FieldView::from(&input), 1, b' ');
 
-    const DIRS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
-
-    let mut dir = 0;
-    let pos =
-        field.tuple_from_offset::<usize>(field.data().iter().position(|&c| c == b'^').unwrap());
-    let mut pos = (pos.0 as i32, pos.1 as i32);
+    let mut dir = Dir4::Up;
+    let mut pos = field.view().find_unique::<i32>(b'^').unwrap();
     let start_pos = pos;
-    let mut visited = HashSet::with_capacity(1000);
-    let mut turns = HashSet::with_capacity(1000);
+    let mut visited = SparseField::with_capacity(1000);
+
+    visited.mark(pos);
 
-    visited.insert(pos);
+    // For every open cell and direction, the offset of the first wall or border cell the guard runs
+    // into from there. Filled against the direction, so the next cell along it is always done first.
+    let data = field.data();
+    let mut stops = vec![0_u32; data.len() * 4];
+    for dir in Dir4::ALL {
+        let delta = field.delta(dir.to_coord::<i32>());
+        let mut fill = |o: usize| {
+            if matches!(data[o], b'#' | b' ') {
+                return;
+            }
+            let next = o.wrapping_add_signed(delta);
+            stops[o * 4 + dir.index()] = if matches!(data[next], b'#' | b' ') {
+                next as u32
+            } else {
+                stops[next * 4 + dir.index()]
+            };
+        };
+        if delta > 0 {
+            (0..data.len()).rev().for_each(&mut fill);
+        } else {
+            (0..data.len()).for_each(&mut fill);
+        }
+    }
 
-    let mut is_looping = |wall_pos: (i32, i32), mut dir: usize| {
-        turns.clear();
-        let mut pos = (wall_pos.0 - DIRS[dir].0, wall_pos.1 - DIRS[dir].1);
-        dir = (dir + 1) & 3;
-        turns.insert((pos, dir));
+    // A loop always passes the same turn twice, so remembering the turns is enough. Turns are marked
+    // by the offset of the wall and the new direction, and stamped with a number that is unique per
+    // check so the marks never need to be cleared.
+    let is_looping = |turns: &mut (Vec<u32>, u32), wall_pos: Coord<i32>, dir: Dir4| {
+        let (marks, stamp) = turns;
+        *stamp += 1;
+        let wall = field.offset_coord(wall_pos) as isize;
+        let mut pos = wall - field.delta(dir.to_coord::<i32>());
+        let mut dir = dir.turn_right();
 
         loop {
-            let newpos = (pos.0 + DIRS[dir].0, pos.1 + DIRS[dir].1);
-            match if newpos == wall_pos {
-                b'#'
-            } else {
-                *field.get(newpos.0 as usize, newpos.1 as usize)
-            } {
-                b'#' => {
-                    dir = (dir + 1) & 3;
-                    if !turns.insert((newpos, dir)) {
-                        return true;
-                    }
-                }
-                b' ' => return false,
-                _ => pos = newpos,
+            let delta = field.delta(dir.to_coord::<i32>());
+            let mut stop = stops[pos as usize * 4 + dir.index()] as isize;
+            // The new wall only matters when it lies between here and where the guard would stop
+            let to_wall = wall - pos;
+            if to_wall % delta == 0 && (1..=(stop - pos) / delta).contains(&(to_wall / delta)) {
+                stop = wall;
+            } else if data[stop as usize] == b' ' {
+                return false;
             }
+
+            dir = dir.turn_right();
+            let mark = &mut marks[stop as usize * 4 + dir.index()];
+            if *mark == *stamp {
+                return true;
+            }
+            *mark = *stamp;
+            pos = stop - delta;
         }
     };
 
-    let mut total2 = 0;
+    let mut candidates = Vec::with_capacity(1000);
 
     loop {
-        let newpos = (pos.0 + DIRS[dir].0, pos.1 + DIRS[dir].1);
-        match field.get(newpos.0 as usize, newpos.1 as usize) {
+        let newpos = pos + dir;
+        match field[newpos] {
             b'#' => {
-                dir = (dir + 1) & 3;
+                dir = dir.turn_right();
             }
             b' ' => break,
             _ => {
-                if visited.insert(newpos) && newpos != start_pos && is_looping(newpos, dir) {
-                    total2 += 1;
+                if visited.mark(newpos) && newpos != start_pos {
+                    candidates.push((newpos, dir));
                 }
 
                 pos = newpos;
@@ -62,6 +86,23 @@ fn solve(input: Input) -> impl AocResult {
     }
 
     let total1 = visited.len();
+    let new_turns = || (vec![0; data.len() * 4], 0);
+    #[cfg(feature = "parallel")]
+    let total2 = ScopedPool::default()
+        .map_with(&candidates, new_turns, |turns, &(wall_pos, dir)| {
+            is_looping(turns, wall_pos, dir)
+        })
+        .into_iter()
+        .filter(|&l| l)
+        .count();
+    #[cfg(not(feature = "parallel"))]
+    let total2 = {
+        let mut turns = new_turns();
+        candidates
+            .iter()
+            .filter(|&&(wall_pos, dir)| is_looping(&mut turns, wall_pos, dir))
+            .count()
+    };
 
     (total1, total2)
 }
@@ -1,15 +1,5 @@
 use util::*;
 
-fn next_pow10(v: usize) -> usize {
-    const POW10S: &[usize] = &[10, 100, 1000, 10000, 100000];
-    for &n in POW10S {
-        if n > v {
-            return n;
-        }
-    }
-    panic!("Unexpectedly large number: {v}")
-}
-
 fn check(target: usize, n: &[usize], do_concat: bool) -> bool {
     if n.len() == 1 {
         return target == n[0];
@@ -22,11 +12,11 @@ fn check(target: usize, n: &[usize], do_concat: bool) -> bool {
     if target > 0 && target < last {
         return false;
     }
-    if last != 0 && target % last == 0 && check(target / last, n, do_concat) {
+    if last != 0 && target.is_multiple_of(last) && check(target / last, n, do_concat) {
         return true;
     }
     if do_concat {
-        let pow10 = next_pow10(last);
+        let pow10 = num::next_pow10(last);
         if target % pow10 == last && check(target / pow10, n, true) {
             return true;
         }
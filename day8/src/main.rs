@@ -1,4 +1,3 @@
-use std::collections::{HashMap, HashSet};
 use util::*;
 
 #[aoc_day]
@@ -10,7 +9,8 @@ fn solve(input: Input) -> impl AocResult {
     let is_valid =
         |coord: Coord<i32>| (0..width).contains(&coord.x) && (0..height).contains(&coord.y);
 
-    let mut map: HashMap<u8, Vec<Coord<i32>>> = HashMap::with_capacity(1000);
+    let mut map: FastHashMap<u8, Vec<Coord<i32>>> =
+        FastHashMap::with_capacity_and_hasher(1000, Default::default());
     for y in 0..height {
         for x in 0..width {
             let c = field[(x, y)];
@@ -22,7 +22,7 @@ fn solve(input: Input) -> impl AocResult {
         }
     }
 
-    let mut found = HashSet::with_capacity(1000);
+    let mut found = SparseField::with_capacity(1000);
 
     for (&k, v) in &map {
         for i in 0..v.len() - 1 {
@@ -32,11 +32,11 @@ fn solve(input: Input) -> impl AocResult {
                 let diff = v1 - v0;
                 let p0 = v0 - diff;
                 if is_valid(p0) && field[p0] != k {
-                    found.insert(p0);
+                    found.mark(p0);
                 }
                 let p1 = v1 + diff;
                 if is_valid(p1) && field[p1] != k {
-                    found.insert(p1);
+                    found.mark(p1);
                 }
 
                 // Apparently this never triggers
@@ -45,11 +45,11 @@ fn solve(input: Input) -> impl AocResult {
                     let diff3 = diff / 3;
                     let p2 = v0 + diff3;
                     if field[p2] != k {
-                        found.insert(p2);
+                        found.mark(p2);
                     }
                     let p3 = v1 - diff3;
                     if field[p3] != k {
-                        found.insert(p3);
+                        found.mark(p3);
                     }
                 }
                 */
@@ -59,33 +59,15 @@ fn solve(input: Input) -> impl AocResult {
 
     let total1 = found.len();
 
-    for (&k, v) in &map {
+    for v in map.values() {
         for i in 0..v.len() - 1 {
             for j in i + 1..v.len() {
-                let v0 = v[i];
-                let v1 = v[j];
-                let diff = v1 - v0;
-
-                // Apparently this never triggers
-                //let diff = diff / gcd(diff.x, diff.y).abs();
-
-                found.insert(v0);
-                found.insert(v1);
-
-                let mut p0 = v0 - diff * 2;
-                while is_valid(p0) {
-                    if field[p0] != k {
-                        found.insert(p0);
-                    }
-                    p0 -= diff;
-                }
-
-                let mut p1 = v1 + diff * 2;
-                while is_valid(p1) {
-                    if field[p1] != k {
-                        found.insert(p1);
+                let (v0, v1) = (v[i], v[j]);
+                let step = geom::lattice_step(v0, v1);
+                for step in [step, -step] {
+                    for p in geom::lattice_ray(v0, step).take_while(|&p| is_valid(p)) {
+                        found.mark(p);
                     }
-                    p1 += diff;
                 }
             }
         }
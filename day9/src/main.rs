@@ -1,16 +1,8 @@
-#![allow(non_camel_case_types)]
-
 use std::ops::*;
 use util::*;
 
-#[cfg(feature = "u128")]
-type uresult = u128;
-
-#[cfg(not(feature = "u128"))]
-type uresult = u64;
-
-fn sum(from: u32, to: u32) -> uresult {
-    ((to as u64 * to.add(1) as u64 - from as u64 * from.sub(1) as u64) / 2) as uresult
+fn sum(from: u32, to: u32) -> u64 {
+    (to as u64 * to.add(1) as u64 - from as u64 * from.sub(1) as u64) / 2
 }
 
 const INVALID: u32 = u32::MAX;
@@ -92,13 +84,13 @@ impl GapList {
 fn solve(input: Input) -> impl AocResult {
     let data = {
         let mut b = input.bytes();
-        while b.len() > 0 && is_nl(b[b.len() - 1]) {
+        while !b.is_empty() && is_nl(b[b.len() - 1]) {
             b = &b[..b.len() - 1];
         }
         b
     };
 
-    let mut total1 = 0;
+    let mut total1 = checked::Sum::new();
 
     let mut front = 0;
     let mut back = data.len() - 1;
@@ -108,7 +100,7 @@ fn solve(input: Input) -> impl AocResult {
     while front < back {
         let f = (data[front] - b'0') as u32;
         // println!("{f}*{}", front / 2);
-        total1 += sum(write, write + f - 1) * (front / 2) as uresult;
+        total1.add_product(sum(write, write + f - 1), (front / 2) as u64);
         write += f;
 
         front += 1;
@@ -116,7 +108,7 @@ fn solve(input: Input) -> impl AocResult {
         while gap > 0 {
             let min = last_len.min(gap);
             // println!("{min}*{}", back / 2);
-            total1 += sum(write, write + min - 1) * (back / 2) as uresult;
+            total1.add_product(sum(write, write + min - 1), (back / 2) as u64);
             last_len -= min;
             gap -= min;
             write += min;
@@ -132,16 +124,16 @@ fn solve(input: Input) -> impl AocResult {
 
     if last_len > 0 {
         // println!("{last_len}*{}", back / 2);
-        total1 += sum(write, write + last_len - 1) * (back / 2) as uresult;
+        total1.add_product(sum(write, write + last_len - 1), (back / 2) as u64);
     }
 
     let mut gap_list = GapList::new(data.len() / 2 + 1);
-    let mut orig_pos = vec![0; (data.len() + 1) / 2];
+    let mut orig_pos = vec![0; data.len().div_ceil(2)];
     let mut offset = 0;
 
     for (idx, e) in data.chunks(2).enumerate() {
         let l = (e[0] - b'0') as u32;
-        orig_pos[idx as usize] = offset;
+        orig_pos[idx] = offset;
         offset += l;
         if e.len() > 1 {
             let g = (e[1] - b'0') as u32;
@@ -152,16 +144,16 @@ fn solve(input: Input) -> impl AocResult {
         }
     }
 
-    let mut total2 = 0;
+    let mut total2 = checked::Sum::new();
 
     for (idx, &len) in data.iter().step_by(2).enumerate().rev() {
         let len = (len - b'0') as u32;
         if let Some(offset) = gap_list.get(orig_pos[idx], len) {
             // println!("{idx}*{len} at {offset} [moved]");
-            total2 += sum(offset, offset + len - 1) * idx as uresult;
+            total2.add_product(sum(offset, offset + len - 1), idx as u64);
         } else {
             // println!("{idx}*{len} at {}", orig_pos[idx]);
-            total2 += sum(orig_pos[idx], orig_pos[idx] + len - 1) * idx as uresult;
+            total2.add_product(sum(orig_pos[idx], orig_pos[idx] + len - 1), idx as u64);
         }
     }
 
@@ -0,0 +1,245 @@
+use crate::ToPrimitive;
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, MulAssign};
+
+// An unsigned integer of any size, for results that don't fit into a u128. Only supports what is
+// needed to accumulate and print results: addition, multiplication and formatting.
+#[derive(Clone, Default, PartialEq, Eq, Hash, Debug)]
+pub struct BigUint {
+    // Little endian, without trailing zero limbs, so zero is empty
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn bits(&self) -> u64 {
+        match self.limbs.last() {
+            Some(&top) => self.limbs.len() as u64 * 32 - top.leading_zeros() as u64,
+            None => 0,
+        }
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        Some(
+            self.limbs
+                .iter()
+                .rev()
+                .fold(0, |acc, &l| acc << 32 | l as u128),
+        )
+    }
+
+    fn trim(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+
+    // Divides in place and returns the remainder
+    fn div_rem_small(&mut self, d: u32) -> u32 {
+        let mut rem = 0_u64;
+        for l in self.limbs.iter_mut().rev() {
+            let cur = rem << 32 | *l as u64;
+            *l = (cur / d as u64) as u32;
+            rem = cur % d as u64;
+        }
+        self.trim();
+        rem as u32
+    }
+}
+
+macro_rules! impl_from_unsigned {
+    ($($t:ty),+) => {
+        $(
+            impl From<$t> for BigUint {
+                fn from(v: $t) -> Self {
+                    let v: u128 = v.to();
+                    let mut r = Self {
+                        limbs: (0..4).map(|i| (v >> (32 * i)) as u32).collect(),
+                    };
+                    r.trim();
+                    r
+                }
+            }
+        )+
+    };
+}
+
+impl_from_unsigned!(u8, u16, u32, u64, u128, usize);
+
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, rhs: &BigUint) {
+        if self.limbs.len() < rhs.limbs.len() {
+            self.limbs.resize(rhs.limbs.len(), 0);
+        }
+        let mut carry = 0_u64;
+        for (i, l) in self.limbs.iter_mut().enumerate() {
+            let cur = *l as u64 + rhs.limbs.get(i).copied().unwrap_or(0) as u64 + carry;
+            *l = cur as u32;
+            carry = cur >> 32;
+            if carry == 0 && i >= rhs.limbs.len() {
+                break;
+            }
+        }
+        if carry != 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+}
+
+impl AddAssign for BigUint {
+    fn add_assign(&mut self, rhs: BigUint) {
+        *self += &rhs;
+    }
+}
+
+impl Add for BigUint {
+    type Output = BigUint;
+    fn add(mut self, rhs: BigUint) -> BigUint {
+        self += &rhs;
+        self
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+    fn mul(self, rhs: &BigUint) -> BigUint {
+        if self.is_zero() || rhs.is_zero() {
+            return BigUint::zero();
+        }
+        let mut limbs = vec![0_u32; self.limbs.len() + rhs.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0_u64;
+            for (j, &b) in rhs.limbs.iter().enumerate() {
+                let cur = a as u64 * b as u64 + limbs[i + j] as u64 + carry;
+                limbs[i + j] = cur as u32;
+                carry = cur >> 32;
+            }
+            limbs[i + rhs.limbs.len()] = carry as u32;
+        }
+        let mut r = BigUint { limbs };
+        r.trim();
+        r
+    }
+}
+
+impl Mul for BigUint {
+    type Output = BigUint;
+    fn mul(self, rhs: BigUint) -> BigUint {
+        &self * &rhs
+    }
+}
+
+impl MulAssign<&BigUint> for BigUint {
+    fn mul_assign(&mut self, rhs: &BigUint) {
+        *self = &*self * rhs;
+    }
+}
+
+impl std::iter::Sum for BigUint {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, v| acc + v)
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const CHUNK: u32 = 1_000_000_000;
+        let mut v = self.clone();
+        let mut chunks = Vec::with_capacity(self.limbs.len() * 32 / 29 + 1);
+        loop {
+            chunks.push(v.div_rem_small(CHUNK));
+            if v.is_zero() {
+                break;
+            }
+        }
+        let mut s = chunks.pop().unwrap().to_string();
+        for c in chunks.iter().rev() {
+            s += &format!("{c:09}");
+        }
+        f.pad_integral(true, "", &s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(v: u128) -> BigUint {
+        BigUint::from(v)
+    }
+
+    #[test]
+    fn carries_across_limbs() {
+        assert_eq!((big(u32::MAX as u128) + big(1)).to_u128(), Some(1 << 32));
+        assert_eq!((big((1 << 96) - 1) + big(1)).to_u128(), Some(1 << 96));
+        // The carry out of the top limb adds a limb, also when the shorter side is on the left
+        let mut v = big(1);
+        v += &big(u128::MAX);
+        assert_eq!((v.bits(), v.to_u128()), (129, None));
+        assert_eq!(v.to_string(), "340282366920938463463374607431768211456");
+
+        let sq = big(u64::MAX as u128) * big(u64::MAX as u128);
+        assert_eq!(sq.to_u128(), Some((u64::MAX as u128).pow(2)));
+        let mut sq = big(u128::MAX);
+        sq *= &big(u128::MAX);
+        assert_eq!(sq.bits(), 256);
+        assert_eq!(
+            sq.to_string(),
+            "115792089237316195423570985008687907852589419931798687112530834793049593217025"
+        );
+    }
+
+    #[test]
+    fn zero() {
+        let z = BigUint::zero();
+        assert!(z.is_zero() && big(0) == z && BigUint::from(0_u8) == z);
+        assert_eq!((z.bits(), z.to_u128()), (0, Some(0)));
+        assert_eq!(z.to_string(), "0");
+        assert!((&z * &big(u128::MAX)).is_zero());
+        assert!((&big(7) * &z).is_zero());
+        assert_eq!(z.clone() + big(5), big(5));
+        assert!(z < big(1));
+    }
+
+    #[test]
+    fn display_and_order() {
+        // Inner chunks of nine digits keep their leading zeros
+        let v = big(1 << 64) * big(1 << 64) * big(1_000_000_000) + big(7);
+        assert_eq!(
+            v.to_string(),
+            "340282366920938463463374607431768211456000000007"
+        );
+        assert_eq!(big(10_u128.pow(18)).to_string(), "1000000000000000000");
+        assert_eq!(format!("{:>6}|{:<4}|", big(42), big(7)), "    42|7   |");
+
+        assert!(big(u128::MAX) < v && big(1 << 64) > big(u64::MAX as u128));
+        let total: BigUint = [u128::MAX, 1, 2].into_iter().map(big).sum();
+        assert_eq!(total, big(u128::MAX) + big(3));
+    }
+}
@@ -0,0 +1,280 @@
+use crate::{coord, Coord, FromPrimitive, Grid, PrimitiveInt, ToPrimitive};
+use std::ops::{BitAndAssign, BitOrAssign, BitXorAssign, Index};
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct BitField {
+    words: Vec<u64>,
+    width: usize,
+    height: usize,
+    row_words: usize,
+}
+
+impl BitField {
+    pub fn new(width: usize, height: usize) -> Self {
+        let row_words = width.div_ceil(64);
+        Self {
+            words: vec![0; row_words * height],
+            width,
+            height,
+            row_words,
+        }
+    }
+
+    pub fn filled(width: usize, height: usize, value: bool) -> Self {
+        let mut r = Self::new(width, height);
+        r.fill(value);
+        r
+    }
+
+    pub fn from_field<F: Grid + ?Sized>(field: &F, mut pred: impl FnMut(&F::Item) -> bool) -> Self {
+        let mut r = Self::new(field.width(), field.height());
+        for y in 0..field.height() {
+            for x in 0..field.width() {
+                if pred(field.cell(x, y)) {
+                    r.words[y * r.row_words + x / 64] |= 1 << (x % 64);
+                }
+            }
+        }
+        r
+    }
+
+    fn bit<I: PrimitiveInt + ToPrimitive<usize>>(&self, x: I, y: I) -> (usize, u64) {
+        let (x, y): (usize, usize) = (x.to(), y.to());
+        debug_assert!(x < self.width && y < self.height);
+        (y * self.row_words + x / 64, 1 << (x % 64))
+    }
+
+    pub fn get<I: PrimitiveInt + ToPrimitive<usize>>(&self, x: I, y: I) -> bool {
+        let (w, mask) = self.bit(x, y);
+        self.words[w] & mask != 0
+    }
+
+    pub fn get_or<I: PrimitiveInt + ToPrimitive<usize>>(&self, x: I, y: I, alt: bool) -> bool {
+        if self.in_bounds(x, y) {
+            self.get(x, y)
+        } else {
+            alt
+        }
+    }
+
+    pub fn set<I: PrimitiveInt + ToPrimitive<usize>>(&mut self, x: I, y: I, value: bool) {
+        let (w, mask) = self.bit(x, y);
+        if value {
+            self.words[w] |= mask;
+        } else {
+            self.words[w] &= !mask;
+        }
+    }
+
+    pub fn insert<I: PrimitiveInt + ToPrimitive<usize>>(&mut self, pos: Coord<I>) -> bool {
+        let (w, mask) = self.bit(pos.x, pos.y);
+        let old = self.words[w];
+        self.words[w] = old | mask;
+        old & mask == 0
+    }
+
+    pub fn remove<I: PrimitiveInt + ToPrimitive<usize>>(&mut self, pos: Coord<I>) -> bool {
+        let (w, mask) = self.bit(pos.x, pos.y);
+        let old = self.words[w];
+        self.words[w] = old & !mask;
+        old & mask != 0
+    }
+
+    pub fn fill(&mut self, value: bool) {
+        self.words.fill(if value { u64::MAX } else { 0 });
+        if value {
+            self.mask_padding();
+        }
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn any(&self) -> bool {
+        self.words.iter().any(|&w| w != 0)
+    }
+
+    pub fn row_words(&self, y: usize) -> &[u64] {
+        &self.words[y * self.row_words..(y + 1) * self.row_words]
+    }
+
+    pub fn row_words_mut(&mut self, y: usize) -> &mut [u64] {
+        &mut self.words[y * self.row_words..(y + 1) * self.row_words]
+    }
+
+    pub fn iter_ones<I: FromPrimitive<usize>>(&self) -> impl Iterator<Item = Coord<I>> + '_ {
+        let row_words = self.row_words;
+        self.words.iter().enumerate().flat_map(move |(i, &w)| {
+            let (y, x0) = (i / row_words, i % row_words * 64);
+            BitIter(w).map(move |b| coord(I::from(x0 + b), I::from(y)))
+        })
+    }
+
+    pub fn invert(&mut self) {
+        for w in &mut self.words {
+            *w = !*w;
+        }
+        self.mask_padding();
+    }
+
+    // Shifts every row towards lower x, dropping the bits that fall off the left edge
+    pub fn shift_left(&mut self, n: usize) {
+        let (words, bits) = (n / 64, n % 64);
+        for row in self.words.chunks_mut(self.row_words) {
+            for i in 0..row.len() {
+                let lo = row.get(i + words).copied().unwrap_or(0);
+                let hi = row.get(i + words + 1).copied().unwrap_or(0);
+                row[i] = if bits == 0 {
+                    lo
+                } else {
+                    lo >> bits | hi << (64 - bits)
+                };
+            }
+        }
+    }
+
+    // Shifts every row towards higher x, dropping the bits that fall off the right edge
+    pub fn shift_right(&mut self, n: usize) {
+        let (words, bits) = (n / 64, n % 64);
+        for row in self.words.chunks_mut(self.row_words) {
+            for i in (0..row.len()).rev() {
+                let hi = i.checked_sub(words).map_or(0, |j| row[j]);
+                let lo = i.checked_sub(words + 1).map_or(0, |j| row[j]);
+                row[i] = if bits == 0 {
+                    hi
+                } else {
+                    hi << bits | lo >> (64 - bits)
+                };
+            }
+        }
+        self.mask_padding();
+    }
+
+    pub fn shift_up(&mut self, n: usize) {
+        let n = n.min(self.height) * self.row_words;
+        self.words.copy_within(n.., 0);
+        let len = self.words.len();
+        self.words[len - n..].fill(0);
+    }
+
+    pub fn shift_down(&mut self, n: usize) {
+        let n = n.min(self.height) * self.row_words;
+        let len = self.words.len();
+        self.words.copy_within(..len - n, n);
+        self.words[..n].fill(0);
+    }
+
+    fn mask_padding(&mut self) {
+        if !self.width.is_multiple_of(64) {
+            let mask = (1 << (self.width % 64)) - 1;
+            for row in self.words.chunks_mut(self.row_words) {
+                *row.last_mut().unwrap() &= mask;
+            }
+        }
+    }
+
+    fn zip_words(&mut self, other: &BitField, f: impl Fn(&mut u64, u64)) {
+        assert!(self.width == other.width && self.height == other.height);
+        for (a, &b) in self.words.iter_mut().zip(&other.words) {
+            f(a, b);
+        }
+    }
+}
+
+struct BitIter(u64);
+
+impl Iterator for BitIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.0 == 0 {
+            return None;
+        }
+        let b = self.0.trailing_zeros() as usize;
+        self.0 &= self.0 - 1;
+        Some(b)
+    }
+}
+
+impl BitAndAssign<&BitField> for BitField {
+    fn bitand_assign(&mut self, rhs: &BitField) {
+        self.zip_words(rhs, |a, b| *a &= b);
+    }
+}
+
+impl BitOrAssign<&BitField> for BitField {
+    fn bitor_assign(&mut self, rhs: &BitField) {
+        self.zip_words(rhs, |a, b| *a |= b);
+    }
+}
+
+impl BitXorAssign<&BitField> for BitField {
+    fn bitxor_assign(&mut self, rhs: &BitField) {
+        self.zip_words(rhs, |a, b| *a ^= b);
+    }
+}
+
+// Cells are single bits, so there's no slice to hand out and no FieldMut; writes go through set,
+// insert and remove instead
+impl Grid for BitField {
+    type Item = bool;
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn cell(&self, x: usize, y: usize) -> &bool {
+        if self.get(x, y) {
+            &true
+        } else {
+            &false
+        }
+    }
+}
+
+impl<I: PrimitiveInt + ToPrimitive<usize>> Index<(I, I)> for BitField {
+    type Output = bool;
+    fn index(&self, pos: (I, I)) -> &Self::Output {
+        self.cell(pos.0.to(), pos.1.to())
+    }
+}
+
+impl<I: PrimitiveInt + ToPrimitive<usize>> Index<Coord<I>> for BitField {
+    type Output = bool;
+    fn index(&self, pos: Coord<I>) -> &Self::Output {
+        &self[(pos.x, pos.y)]
+    }
+}
+
+impl std::fmt::Display for BitField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.render_with(|&b| if b { '#' } else { '.' }).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FieldView;
+
+    #[test]
+    fn generic_grid_helpers() {
+        let maze = FieldView::from(b"..#.\n.##.\n....\n".as_slice());
+        let open = BitField::from_field(&maze, |&c| c == b'.');
+        assert_eq!(open.to_string(), "##.#\n#..#\n####\n");
+        assert!(open.in_bounds(3, 2) && !open.in_bounds(4, 0) && !open.in_bounds(0, -1));
+
+        let dist = open.bfs_distances([coord(0, 0)], |&b| b);
+        assert_eq!(dist[(3_usize, 0_usize)], 7);
+        assert_eq!(dist[(2_usize, 0_usize)], crate::UNREACHABLE);
+        let marked = open
+            .render_with(|&b| if b { ' ' } else { '#' })
+            .mark([coord(3, 0)], 'E');
+        assert_eq!(marked.to_string(), "  #E\n ## \n    \n");
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{BigUint, Int};
+use std::fmt;
+use std::ops::AddAssign;
+
+// The integers a Sum can fall back to a BigUint from
+pub trait Unsigned: Int + Into<BigUint> {}
+
+impl<T: Int + Into<BigUint>> Unsigned for T {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Integer overflow")
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+// An accumulator that adds up values of type T for as long as the total fits, and switches over to a
+// BigUint when it doesn't. Either way it prints the exact total, so it can be returned directly as a
+// part of an AocResult.
+#[derive(Clone, Debug, Default)]
+pub struct Sum<T> {
+    small: T,
+    big: Option<BigUint>,
+}
+
+impl<T: Unsigned> Sum<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_product(&mut self, a: T, b: T) {
+        match a.checked_mul(b) {
+            Some(v) => *self += v,
+            None => self.add_big(&(a.into() * b.into())),
+        }
+    }
+
+    fn add_big(&mut self, v: &BigUint) {
+        let big = self.big.get_or_insert_with(BigUint::zero);
+        *big += v;
+    }
+
+    pub fn is_overflowed(&self) -> bool {
+        self.big.is_some()
+    }
+
+    // The total, or an error if it doesn't fit into T
+    pub fn value(&self) -> Result<T, Overflow> {
+        match self.big {
+            None => Ok(self.small),
+            Some(_) => Err(Overflow),
+        }
+    }
+
+    pub fn to_big(&self) -> BigUint {
+        let mut r: BigUint = self.small.into();
+        if let Some(big) = &self.big {
+            r += big;
+        }
+        r
+    }
+}
+
+impl<T: Unsigned> AddAssign<T> for Sum<T> {
+    fn add_assign(&mut self, v: T) {
+        match self.small.checked_add(v) {
+            Some(s) if self.big.is_none() => self.small = s,
+            _ => self.add_big(&v.into()),
+        }
+    }
+}
+
+impl<T: Unsigned> std::iter::Sum<T> for Sum<T> {
+    fn sum<I: Iterator<Item = T>>(iter: I) -> Self {
+        let mut r = Self::new();
+        for v in iter {
+            r += v;
+        }
+        r
+    }
+}
+
+impl<T: Unsigned> fmt::Display for Sum<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.big {
+            None => self.small.fmt(f),
+            Some(_) => self.to_big().fmt(f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sum_at_the_u64_limit() {
+        let mut s = Sum::<u64>::new();
+        s += u64::MAX - 1;
+        s += 1;
+        assert_eq!((s.is_overflowed(), s.value()), (false, Ok(u64::MAX)));
+        s += 1;
+        assert!(s.is_overflowed());
+        assert_eq!(s.value(), Err(Overflow));
+        assert_eq!(s.to_string(), "18446744073709551616");
+        // Small values keep adding up once it overflowed
+        s += u64::MAX - 2;
+        assert_eq!(s.to_string(), "36893488147419103229");
+        assert_eq!(s.to_big().bits(), 65);
+
+        let s: Sum<u64> = [u64::MAX, 5, 7].into_iter().sum();
+        assert_eq!(s.to_string(), "18446744073709551627");
+        assert_eq!(Overflow.to_string(), "Integer overflow");
+    }
+
+    #[test]
+    fn sum_at_the_u128_limit() {
+        let s: Sum<u128> = [u128::MAX, 0].into_iter().sum();
+        assert_eq!(s.value(), Ok(u128::MAX));
+        let s: Sum<u128> = [u128::MAX, 1].into_iter().sum();
+        assert_eq!(s.value(), Err(Overflow));
+        assert_eq!(s.to_string(), "340282366920938463463374607431768211456");
+    }
+
+    #[test]
+    fn products() {
+        let mut s = Sum::<u64>::new();
+        s.add_product(1 << 32, (1 << 31) + 1);
+        assert_eq!(s.value(), Ok((1 << 63) + (1 << 32)));
+        s.add_product(u64::MAX, u64::MAX);
+        assert!(s.is_overflowed());
+        assert_eq!(
+            s.to_big(),
+            BigUint::from((u64::MAX as u128).pow(2) + (1 << 63) + (1 << 32))
+        );
+        assert_eq!(Sum::<u32>::new().to_string(), "0");
+    }
+}
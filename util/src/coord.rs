@@ -1,12 +1,19 @@
-use crate::{Decrement, Increment, Primitive, ToPrimitive};
+use crate::{Decrement, Euclid, HashPair, Increment, Primitive, Signed, ToPrimitive};
+use std::hash::{Hash, Hasher};
 use std::ops::*;
 
-#[derive(Default, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
+#[derive(Default, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug)]
 pub struct Coord<T> {
     pub x: T,
     pub y: T,
 }
 
+impl<T: HashPair> Hash for Coord<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        T::hash_pair(&self.x, &self.y, state);
+    }
+}
+
 pub const fn coord<T>(x: T, y: T) -> Coord<T> {
     Coord { x, y }
 }
@@ -32,6 +39,64 @@ impl<T> Coord<T> {
     }
 }
 
+impl<T: Ord + Copy> Coord<T> {
+    // Componentwise, unlike the lexicographic Ord::min, Ord::max and Ord::clamp
+    pub fn component_min(self, other: Self) -> Self {
+        coord(self.x.min(other.x), self.y.min(other.y))
+    }
+
+    pub fn component_max(self, other: Self) -> Self {
+        coord(self.x.max(other.x), self.y.max(other.y))
+    }
+
+    pub fn component_clamp(self, min: Self, max: Self) -> Self {
+        coord(self.x.clamp(min.x, max.x), self.y.clamp(min.y, max.y))
+    }
+}
+
+impl<T: Ord + Copy + Sub<Output = T> + Add<Output = T>> Coord<T> {
+    pub fn manhattan(self, other: Self) -> T {
+        let d = self.component_max(other) - self.component_min(other);
+        d.x + d.y
+    }
+
+    pub fn chebyshev(self, other: Self) -> T {
+        let d = self.component_max(other) - self.component_min(other);
+        d.x.max(d.y)
+    }
+}
+
+impl<T: Mul<Output = T> + Add<Output = T> + Sub<Output = T> + Copy> Coord<T> {
+    pub fn dot(self, other: Self) -> T {
+        self.x * other.x + self.y * other.y
+    }
+
+    // The z component of the 3D cross product, positive if `other` lies clockwise of self on screen
+    pub fn cross(self, other: Self) -> T {
+        self.x * other.y - self.y * other.x
+    }
+}
+
+impl<T: Signed> Coord<T> {
+    pub fn abs(self) -> Self {
+        coord(self.x.abs(), self.y.abs())
+    }
+
+    pub fn signum(self) -> Self {
+        coord(self.x.signum(), self.y.signum())
+    }
+}
+
+impl<T: Euclid> Coord<T> {
+    pub fn rem_euclid(self, rhs: Self) -> Self {
+        coord(self.x.rem_euclid(rhs.x), self.y.rem_euclid(rhs.y))
+    }
+
+    pub fn div_euclid(self, rhs: Self) -> Self {
+        coord(self.x.div_euclid(rhs.x), self.y.div_euclid(rhs.y))
+    }
+}
+
 impl<T: Increment + Decrement> Coord<T> {
     pub fn left(&self) -> Self {
         coord(self.x.get_dec(), self.y)
@@ -210,3 +275,27 @@ macro_rules! impl_rhs_mul {
 }
 
 impl_rhs_mul!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn componentwise_vs_ord() {
+        let (a, b) = (coord(1, 5), coord(3, 2));
+        assert_eq!(
+            (a.component_min(b), a.component_max(b)),
+            (coord(1, 2), coord(3, 5))
+        );
+        assert_eq!((a.min(b), a.max(b)), (a, b));
+        assert_eq!(coord(-4, 9).component_clamp(coord(0, 0), b), coord(0, 2));
+        assert_eq!((a.manhattan(b), a.chebyshev(b)), (5, 3));
+        assert_eq!(
+            (
+                coord(4u8, 0).manhattan(coord(1, 2)),
+                coord(4u8, 0).chebyshev(coord(1, 2))
+            ),
+            (5, 3)
+        );
+    }
+}
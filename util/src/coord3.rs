@@ -0,0 +1,229 @@
+use crate::{Decrement, Increment, Primitive, ToPrimitive};
+use std::ops::*;
+
+#[derive(Default, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
+pub struct Coord3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+pub const fn coord3<T>(x: T, y: T, z: T) -> Coord3<T> {
+    Coord3 { x, y, z }
+}
+
+impl<T> Coord3<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn to<U: Primitive>(self) -> Coord3<U>
+    where
+        T: ToPrimitive<U> + Copy,
+    {
+        coord3(self.x.to(), self.y.to(), self.z.to())
+    }
+
+    pub fn tuple(self) -> (T, T, T) {
+        (self.x, self.y, self.z)
+    }
+}
+
+impl<T: Ord + Sub<Output = T> + Add<Output = T> + Copy> Coord3<T> {
+    pub fn manhattan(self, other: Self) -> T {
+        let d = |a: T, b: T| a.max(b) - a.min(b);
+        d(self.x, other.x) + d(self.y, other.y) + d(self.z, other.z)
+    }
+}
+
+impl<T: Increment + Decrement> Coord3<T> {
+    pub fn neighbors6(&self) -> [Self; 6] {
+        let Coord3 { x, y, z } = *self;
+        [
+            coord3(x.get_dec(), y, z),
+            coord3(x.get_inc(), y, z),
+            coord3(x, y.get_dec(), z),
+            coord3(x, y.get_inc(), z),
+            coord3(x, y, z.get_dec()),
+            coord3(x, y, z.get_inc()),
+        ]
+    }
+
+    // All cells of the surrounding 3x3x3 cube except for self, ordered by z, then y, then x
+    pub fn neighbors26(&self) -> [Self; 26] {
+        let Coord3 { x, y, z } = *self;
+        let (xs, ys, zs) = (
+            [x.get_dec(), x, x.get_inc()],
+            [y.get_dec(), y, y.get_inc()],
+            [z.get_dec(), z, z.get_inc()],
+        );
+        std::array::from_fn(|i| {
+            let i = if i < 13 { i } else { i + 1 };
+            coord3(xs[i % 3], ys[i / 3 % 3], zs[i / 9])
+        })
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Coord3<T> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        coord3(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Coord3<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        coord3(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Coord3<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Coord3<T> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        coord3(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl<T: SubAssign> SubAssign for Coord3<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Coord3<T> {
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self::Output {
+        coord3(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl<T: MulAssign + Copy> MulAssign<T> for Coord3<T> {
+    fn mul_assign(&mut self, rhs: T) {
+        self.x *= rhs;
+        self.y *= rhs;
+        self.z *= rhs;
+    }
+}
+
+impl<T: Div<Output = T> + Copy> Div<T> for Coord3<T> {
+    type Output = Self;
+    fn div(self, rhs: T) -> Self::Output {
+        coord3(self.x / rhs, self.y / rhs, self.z / rhs)
+    }
+}
+
+impl<T: DivAssign + Copy> DivAssign<T> for Coord3<T> {
+    fn div_assign(&mut self, rhs: T) {
+        self.x /= rhs;
+        self.y /= rhs;
+        self.z /= rhs;
+    }
+}
+
+impl<T: Rem<Output = T> + Copy> Rem<T> for Coord3<T> {
+    type Output = Self;
+    fn rem(self, rhs: T) -> Self::Output {
+        coord3(self.x % rhs, self.y % rhs, self.z % rhs)
+    }
+}
+
+impl<T: RemAssign + Copy> RemAssign<T> for Coord3<T> {
+    fn rem_assign(&mut self, rhs: T) {
+        self.x %= rhs;
+        self.y %= rhs;
+        self.z %= rhs;
+    }
+}
+
+impl<T> From<(T, T, T)> for Coord3<T> {
+    fn from(value: (T, T, T)) -> Self {
+        coord3(value.0, value.1, value.2)
+    }
+}
+
+impl<T> From<Coord3<T>> for (T, T, T) {
+    fn from(value: Coord3<T>) -> Self {
+        (value.x, value.y, value.z)
+    }
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for Coord3<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+macro_rules! impl_rhs_mul {
+    ($($t:ty),+) => {
+        $(
+            impl Mul<Coord3<$t>> for $t {
+                type Output = Coord3<$t>;
+                fn mul(self, rhs: Coord3<$t>) -> Self::Output {
+                    rhs * self
+                }
+            }
+        )+
+    };
+}
+
+impl_rhs_mul!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let a = coord3(1, -2, 3);
+        let b = coord3(4, 5, -6);
+        assert_eq!(a + b, coord3(5, 3, -3));
+        assert_eq!(a - b, coord3(-3, -7, 9));
+        assert_eq!(-a, coord3(-1, 2, -3));
+        assert_eq!(a * 3, coord3(3, -6, 9));
+        assert_eq!(3 * a, a * 3);
+        assert_eq!(b / 2, coord3(2, 2, -3));
+        assert_eq!(b % 4, coord3(0, 1, -2));
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+        c *= 2;
+        c /= 4;
+        c %= 2;
+        assert_eq!(c, coord3(0, 0, -1));
+        assert_eq!(a.to::<i64>(), coord3(1_i64, -2, 3));
+        assert_eq!(Coord3::from(a.tuple()), a);
+        assert_eq!(a.to_string(), "(1, -2, 3)");
+    }
+
+    #[test]
+    fn manhattan() {
+        assert_eq!(coord3(1, -2, 3).manhattan(coord3(4, 5, -6)), 19);
+        // Unsigned coordinates don't underflow either way around
+        assert_eq!(coord3(1_u32, 9, 3).manhattan(coord3(4, 5, 3)), 7);
+        assert_eq!(coord3(4_u32, 5, 3).manhattan(coord3(1, 9, 3)), 7);
+    }
+
+    #[test]
+    fn neighbors() {
+        let p = coord3(5, 0, -5);
+        assert!(p.neighbors6().iter().all(|&n| n.manhattan(p) == 1));
+        let n26 = p.neighbors26();
+        assert!(!n26.contains(&p));
+        assert!(n26.is_sorted_by_key(|n| (n.z, n.y, n.x)));
+        assert_eq!(n26[0], coord3(4, -1, -6));
+        assert_eq!(n26[25], coord3(6, 1, -4));
+    }
+}
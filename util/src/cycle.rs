@@ -0,0 +1,188 @@
+use crate::{FastHashMap, Field, FxHasher};
+use std::{
+    collections::hash_map::Entry,
+    hash::{Hash, Hasher},
+};
+
+// The sequence start, step(start), step(step(start)), ... enters a cycle after `start` steps, and then
+// repeats every `len` steps. `first` is the state at which the cycle is entered.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cycle<S> {
+    pub start: usize,
+    pub len: usize,
+    pub first: S,
+}
+
+impl<S> Cycle<S> {
+    // Maps any step index onto the equivalent step index before the end of the first cycle
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.len
+        }
+    }
+}
+
+// Brent's algorithm, which only keeps two states around and calls `step` fewer times than Floyd's
+pub fn brent<S: Clone + Eq>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle<S> {
+    let mut power = 1;
+    let mut len = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == len {
+            tortoise = hare.clone();
+            power *= 2;
+            len = 0;
+        }
+        hare = step(&hare);
+        len += 1;
+    }
+
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..len {
+        hare = step(&hare);
+    }
+    let mut mu = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        mu += 1;
+    }
+
+    Cycle {
+        start: mu,
+        len,
+        first: tortoise,
+    }
+}
+
+pub fn floyd<S: Clone + Eq>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle<S> {
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        let h = step(&hare);
+        hare = step(&h);
+    }
+
+    let mut mu = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        mu += 1;
+    }
+
+    let mut len = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        len += 1;
+    }
+
+    Cycle {
+        start: mu,
+        len,
+        first: tortoise,
+    }
+}
+
+// Returns the state after `n` steps. Every state is remembered by its key, and as soon as a key repeats
+// the remaining steps are skipped. States are expected to be equal iff their keys are equal, so when
+// using `field_hash` as the key a hash collision will produce a wrong answer.
+pub fn simulate_by_key<S, K: Eq + Hash>(
+    start: S,
+    n: usize,
+    mut key: impl FnMut(&S) -> K,
+    mut step: impl FnMut(&S) -> S,
+) -> S {
+    let mut seen = FastHashMap::<K, usize>::default();
+    let mut states = Vec::new();
+    let mut cur = start;
+    for i in 0..n {
+        match seen.entry(key(&cur)) {
+            Entry::Occupied(e) => {
+                let j = *e.get();
+                return states.swap_remove(j + (n - j) % (i - j));
+            }
+            Entry::Vacant(e) => {
+                e.insert(i);
+            }
+        }
+        let next = step(&cur);
+        states.push(cur);
+        cur = next;
+    }
+    cur
+}
+
+pub fn simulate<S: Clone + Eq + Hash>(start: S, n: usize, step: impl FnMut(&S) -> S) -> S {
+    simulate_by_key(start, n, |s| s.clone(), step)
+}
+
+pub fn field_hash<F: Field + ?Sized>(field: &F) -> u64
+where
+    F::Item: Hash,
+{
+    let mut h = FxHasher::default();
+    for row in field.rows() {
+        Hash::hash_slice(row, &mut h);
+    }
+    h.write_usize(field.width());
+    h.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Step = fn(&u32) -> u32;
+
+    // 0, 1, 2, 3 lead into the cycle 4..=9
+    fn rho(&x: &u32) -> u32 {
+        if x < 9 {
+            x + 1
+        } else {
+            4
+        }
+    }
+
+    #[test]
+    fn detect() {
+        let cases: [(Step, usize, usize, u32); 3] = [
+            (rho, 4, 6, 4),
+            // The cycle starts right away
+            (|&x| (x + 1) % 5, 0, 5, 0),
+            (|&x| (x + 1).min(3), 3, 1, 3),
+        ];
+        for (step, start, len, first) in cases {
+            let expected = Cycle { start, len, first };
+            assert_eq!(brent(0, step), expected);
+            assert_eq!(floyd(0, step), expected);
+        }
+    }
+
+    #[test]
+    fn reduce_and_simulate() {
+        let c = brent(0, rho);
+        assert_eq!(
+            (c.reduce(3), c.reduce(4), c.reduce(10), c.reduce(15)),
+            (3, 4, 4, 9)
+        );
+        for n in [0, 3, 4, 9, 10, 1000, 1_000_003] {
+            let direct = (0..n.min(100)).fold(0, |x, _| rho(&x));
+            let expected = if n < 100 { direct } else { c.reduce(n) as u32 };
+            assert_eq!(simulate(0, n, rho), expected, "n = {n}");
+        }
+        assert_eq!(simulate(0, 1_000_001, |&x: &u32| (x + 1) % 5), 1);
+        // The key only has to identify the state, here the second half follows from the first
+        let step = |&(x, _): &(u32, String)| ((x + 1) % 4, "ab".repeat((x as usize + 1) % 4));
+        assert_eq!(
+            simulate_by_key((0, String::new()), 23, |s| s.0, step),
+            (3, "ababab".to_string())
+        );
+    }
+}
@@ -0,0 +1,416 @@
+use crate::{coord, Coord, FromPrimitive};
+use std::{
+    marker::PhantomData,
+    ops::{Add, AddAssign, BitAnd, BitOr, BitOrAssign, Not, Sub, SubAssign},
+};
+
+pub trait Direction: Copy + Eq {
+    const COUNT: usize;
+    fn index(self) -> usize;
+    fn from_index(index: usize) -> Self;
+    fn dx_dy(self) -> (i32, i32);
+
+    fn all() -> impl Iterator<Item = Self> {
+        (0..Self::COUNT).map(Self::from_index)
+    }
+
+    fn to_coord<T: FromPrimitive<i32>>(self) -> Coord<T> {
+        let (x, y) = self.dx_dy();
+        coord(T::from(x), T::from(y))
+    }
+
+    // For Dir8 these turn by 45 degrees
+    fn turn_left(self) -> Self {
+        Self::from_index((self.index() + Self::COUNT - 1) % Self::COUNT)
+    }
+
+    fn turn_right(self) -> Self {
+        Self::from_index((self.index() + 1) % Self::COUNT)
+    }
+
+    fn reverse(self) -> Self {
+        Self::from_index((self.index() + Self::COUNT / 2) % Self::COUNT)
+    }
+}
+
+// Directions are numbered clockwise starting at Up, with y pointing down as it does in the fields
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum Dir4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Dir4 {
+    pub const ALL: [Dir4; 4] = [Dir4::Up, Dir4::Right, Dir4::Down, Dir4::Left];
+
+    pub fn is_horizontal(self) -> bool {
+        matches!(self, Dir4::Left | Dir4::Right)
+    }
+
+    pub fn is_vertical(self) -> bool {
+        !self.is_horizontal()
+    }
+
+    pub fn from_arrow(c: u8) -> Option<Self> {
+        match c {
+            b'^' => Some(Dir4::Up),
+            b'>' => Some(Dir4::Right),
+            b'v' => Some(Dir4::Down),
+            b'<' => Some(Dir4::Left),
+            _ => None,
+        }
+    }
+
+    // Accepts both UDLR and compass NESW letters
+    pub fn from_letter(c: u8) -> Option<Self> {
+        match c {
+            b'U' | b'N' => Some(Dir4::Up),
+            b'R' | b'E' => Some(Dir4::Right),
+            b'D' | b'S' => Some(Dir4::Down),
+            b'L' | b'W' => Some(Dir4::Left),
+            _ => None,
+        }
+    }
+
+    pub fn to_arrow(self) -> u8 {
+        b"^>v<"[self as usize]
+    }
+}
+
+impl Direction for Dir4 {
+    const COUNT: usize = 4;
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        Self::ALL[index]
+    }
+
+    fn dx_dy(self) -> (i32, i32) {
+        [(0, -1), (1, 0), (0, 1), (-1, 0)][self as usize]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum Dir8 {
+    Up,
+    RightUp,
+    Right,
+    RightDown,
+    Down,
+    LeftDown,
+    Left,
+    LeftUp,
+}
+
+impl Dir8 {
+    pub const ALL: [Dir8; 8] = [
+        Dir8::Up,
+        Dir8::RightUp,
+        Dir8::Right,
+        Dir8::RightDown,
+        Dir8::Down,
+        Dir8::LeftDown,
+        Dir8::Left,
+        Dir8::LeftUp,
+    ];
+
+    pub fn is_diagonal(self) -> bool {
+        self as usize & 1 != 0
+    }
+}
+
+impl Direction for Dir8 {
+    const COUNT: usize = 8;
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        Self::ALL[index]
+    }
+
+    fn dx_dy(self) -> (i32, i32) {
+        [
+            (0, -1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+            (0, 1),
+            (-1, 1),
+            (-1, 0),
+            (-1, -1),
+        ][self as usize]
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(d: Dir4) -> Self {
+        Self::ALL[d as usize * 2]
+    }
+}
+
+macro_rules! impl_coord_ops {
+    ($($d:ty),+) => {
+        $(
+            impl<T: FromPrimitive<i32> + Add<Output = T>> Add<$d> for Coord<T> {
+                type Output = Self;
+                fn add(self, rhs: $d) -> Self::Output {
+                    self + rhs.to_coord()
+                }
+            }
+
+            impl<T: FromPrimitive<i32> + AddAssign> AddAssign<$d> for Coord<T> {
+                fn add_assign(&mut self, rhs: $d) {
+                    *self += rhs.to_coord();
+                }
+            }
+
+            impl<T: FromPrimitive<i32> + Sub<Output = T>> Sub<$d> for Coord<T> {
+                type Output = Self;
+                fn sub(self, rhs: $d) -> Self::Output {
+                    self - rhs.to_coord()
+                }
+            }
+
+            impl<T: FromPrimitive<i32> + SubAssign> SubAssign<$d> for Coord<T> {
+                fn sub_assign(&mut self, rhs: $d) {
+                    *self -= rhs.to_coord();
+                }
+            }
+        )+
+    };
+}
+
+impl_coord_ops!(Dir4, Dir8);
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DirSet<D> {
+    bits: u8,
+    _dir: PhantomData<D>,
+}
+
+impl<D: Direction> DirSet<D> {
+    pub const EMPTY: Self = Self {
+        bits: 0,
+        _dir: PhantomData,
+    };
+
+    pub fn all() -> Self {
+        Self::from_bits(((1u16 << D::COUNT) - 1) as u8)
+    }
+
+    pub fn from_bits(bits: u8) -> Self {
+        Self {
+            bits,
+            _dir: PhantomData,
+        }
+    }
+
+    pub fn bits(self) -> u8 {
+        self.bits
+    }
+
+    pub fn len(self) -> usize {
+        self.bits.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.bits == 0
+    }
+
+    pub fn contains(self, d: D) -> bool {
+        self.bits & 1 << d.index() != 0
+    }
+
+    // Returns whether the direction wasn't in the set yet
+    pub fn insert(&mut self, d: D) -> bool {
+        let old = self.bits;
+        self.bits |= 1 << d.index();
+        old != self.bits
+    }
+
+    pub fn remove(&mut self, d: D) -> bool {
+        let old = self.bits;
+        self.bits &= !(1 << d.index());
+        old != self.bits
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = D> {
+        let mut bits = self.bits;
+        std::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+            let i = bits.trailing_zeros() as usize;
+            bits &= bits - 1;
+            Some(D::from_index(i))
+        })
+    }
+}
+
+impl<D: Direction> Default for DirSet<D> {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+impl<D: Direction> FromIterator<D> for DirSet<D> {
+    fn from_iter<It: IntoIterator<Item = D>>(iter: It) -> Self {
+        let mut r = Self::EMPTY;
+        for d in iter {
+            r.insert(d);
+        }
+        r
+    }
+}
+
+impl<D: Direction> From<D> for DirSet<D> {
+    fn from(d: D) -> Self {
+        Self::from_bits(1 << d.index())
+    }
+}
+
+impl<D: Direction> BitOr for DirSet<D> {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self::from_bits(self.bits | rhs.bits)
+    }
+}
+
+impl<D: Direction> BitOr<D> for DirSet<D> {
+    type Output = Self;
+    fn bitor(self, rhs: D) -> Self::Output {
+        self | Self::from(rhs)
+    }
+}
+
+impl<D: Direction> BitOrAssign<D> for DirSet<D> {
+    fn bitor_assign(&mut self, rhs: D) {
+        self.insert(rhs);
+    }
+}
+
+impl<D: Direction> BitAnd for DirSet<D> {
+    type Output = Self;
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self::from_bits(self.bits & rhs.bits)
+    }
+}
+
+impl<D: Direction> Not for DirSet<D> {
+    type Output = Self;
+    fn not(self) -> Self::Output {
+        Self::from_bits(!self.bits & Self::all().bits)
+    }
+}
+
+impl<D: Direction + std::fmt::Debug> std::fmt::Debug for DirSet<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trips<D: Direction + std::fmt::Debug>() {
+        for d in D::all() {
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(d.turn_right().turn_left(), d);
+            assert_eq!(d.reverse().reverse(), d);
+            assert_ne!(d.reverse(), d);
+            let quarter = (0..D::COUNT / 4).fold(d, |d, _| d.turn_right());
+            assert_eq!(
+                quarter.reverse(),
+                (0..D::COUNT / 4).fold(d, |d, _| d.turn_left())
+            );
+            assert_eq!(d.reverse().to_coord::<i32>(), -d.to_coord::<i32>());
+            assert_eq!(D::from_index(d.index()), d);
+        }
+        assert_eq!(D::all().count(), D::COUNT);
+    }
+
+    #[test]
+    fn turns_and_reversal() {
+        round_trips::<Dir4>();
+        round_trips::<Dir8>();
+        assert_eq!(Dir4::Up.turn_right(), Dir4::Right);
+        assert_eq!(Dir4::Up.turn_left(), Dir4::Left);
+        assert_eq!(Dir8::Up.turn_left(), Dir8::LeftUp);
+        assert_eq!(Dir8::RightDown.reverse(), Dir8::LeftUp);
+    }
+
+    #[test]
+    fn parsing() {
+        for d in Dir4::ALL {
+            assert_eq!(Dir4::from_arrow(d.to_arrow()), Some(d));
+        }
+        assert_eq!(Dir4::from_arrow(b'v'), Some(Dir4::Down));
+        assert_eq!(Dir4::from_arrow(b'V'), None);
+        assert_eq!(Dir4::from_arrow(b'.'), None);
+        assert_eq!(
+            b"URDLNESW".map(|c| Dir4::from_letter(c).unwrap()),
+            [Dir4::Up, Dir4::Right, Dir4::Down, Dir4::Left].repeat(2)[..]
+        );
+        assert_eq!(Dir4::from_letter(b'u'), None);
+        assert_eq!(Dir4::from_letter(b'^'), None);
+    }
+
+    #[test]
+    fn coords() {
+        // y points down
+        assert_eq!(Dir4::Up.to_coord::<i32>(), coord(0, -1));
+        assert_eq!(Dir8::LeftDown.to_coord::<i64>(), coord(-1, 1));
+        assert_eq!(coord(3, 3) + Dir4::Right, coord(4, 3));
+        assert_eq!(coord(3_i64, 3) - Dir8::RightUp, coord(2, 4));
+        let mut c = coord(0, 0);
+        c += Dir8::LeftUp;
+        c -= Dir4::Down;
+        assert_eq!(c, coord(-1, -2));
+        for d in Dir4::ALL {
+            let d8 = Dir8::from(d);
+            assert!(!d8.is_diagonal());
+            assert_eq!(d8.to_coord::<i32>(), d.to_coord());
+            assert_eq!(d.is_horizontal(), d.to_coord::<i32>().y == 0);
+        }
+        assert!(Dir8::ALL.iter().filter(|d| d.is_diagonal()).all(|d| {
+            let c = d.to_coord::<i32>();
+            c.x != 0 && c.y != 0
+        }));
+    }
+
+    #[test]
+    fn dir_sets() {
+        let mut s = DirSet::<Dir4>::EMPTY;
+        assert!(s.is_empty() && s.iter().next().is_none());
+        assert!(s.insert(Dir4::Left));
+        assert!(!s.insert(Dir4::Left));
+        s |= Dir4::Up;
+        assert_eq!(s.iter().collect::<Vec<_>>(), [Dir4::Up, Dir4::Left]);
+        assert_eq!((s.len(), s.bits()), (2, 0b1001));
+        assert!(s.contains(Dir4::Up) && !s.contains(Dir4::Down));
+
+        // Not only flips the bits of directions that exist
+        assert_eq!(!s, DirSet::from(Dir4::Right) | Dir4::Down);
+        assert_eq!((!s).bits(), 0b0110);
+        assert_eq!(!DirSet::<Dir4>::all(), DirSet::EMPTY);
+        assert_eq!(!DirSet::<Dir8>::EMPTY, DirSet::all());
+        assert_eq!(DirSet::<Dir8>::all().len(), 8);
+
+        let t: DirSet<Dir4> = [Dir4::Up, Dir4::Right].into_iter().collect();
+        assert_eq!((s & t).iter().collect::<Vec<_>>(), [Dir4::Up]);
+        assert_eq!((s | t).len(), 3);
+        assert!(s.remove(Dir4::Up));
+        assert!(!s.remove(Dir4::Up));
+        assert_eq!(format!("{s:?}"), "{Left}");
+    }
+}
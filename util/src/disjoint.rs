@@ -0,0 +1,193 @@
+// Union-find over the elements 0..len, where every set carries a payload of type T. Uses path halving
+// and union by size, so all operations are effectively constant time.
+#[derive(Clone, Debug, Default)]
+pub struct DisjointSet<T = ()> {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    // Only the root of each set holds a payload
+    payload: Vec<Option<T>>,
+    components: usize,
+}
+
+impl DisjointSet<()> {
+    pub fn with_len(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+            payload: vec![Some(()); len],
+            components: len,
+        }
+    }
+
+    // Returns false if a and b were already in the same set
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        if self.same_set(a, b) {
+            return false;
+        }
+        self.union_with(a, b, |_, _| ());
+        true
+    }
+}
+
+impl<T> DisjointSet<T> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            parent: Vec::with_capacity(capacity),
+            size: Vec::with_capacity(capacity),
+            payload: Vec::with_capacity(capacity),
+            components: 0,
+        }
+    }
+
+    // Adds a new element in a set of its own and returns its index
+    pub fn push(&mut self, value: T) -> usize {
+        let i = self.parent.len();
+        self.parent.push(i);
+        self.size.push(1);
+        self.payload.push(Some(value));
+        self.components += 1;
+        i
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    // The number of disjoint sets
+    pub fn components(&self) -> usize {
+        self.components
+    }
+
+    pub fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            let grandparent = self.parent[self.parent[i]];
+            self.parent[i] = grandparent;
+            i = grandparent;
+        }
+        i
+    }
+
+    pub fn same_set(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    pub fn set_size(&mut self, i: usize) -> usize {
+        let root = self.find(i);
+        self.size[root]
+    }
+
+    // The payload of the set containing i
+    pub fn get(&mut self, i: usize) -> &T {
+        let root = self.find(i);
+        self.payload[root].as_ref().unwrap()
+    }
+
+    pub fn get_mut(&mut self, i: usize) -> &mut T {
+        let root = self.find(i);
+        self.payload[root].as_mut().unwrap()
+    }
+
+    // Joins the sets containing a and b, calling `merge` with the payload of a's set and then b's set.
+    // Returns the root of the joined set. If both are already in the same set nothing is merged.
+    pub fn union_with(&mut self, a: usize, b: usize, merge: impl FnOnce(T, T) -> T) -> usize {
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra == rb {
+            return ra;
+        }
+        let (root, child) = if self.size[ra] >= self.size[rb] {
+            (ra, rb)
+        } else {
+            (rb, ra)
+        };
+        self.parent[child] = root;
+        self.size[root] += self.size[child];
+        self.components -= 1;
+
+        let pa = self.payload[ra].take().unwrap();
+        let pb = self.payload[rb].take().unwrap();
+        self.payload[root] = Some(merge(pa, pb));
+        root
+    }
+
+    // The root and payload of every set
+    pub fn sets(&self) -> impl Iterator<Item = (usize, &T)> {
+        self.payload
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| Some((i, p.as_ref()?)))
+    }
+
+    // The members of every set, in order of their smallest element
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        let mut index = vec![usize::MAX; self.len()];
+        let mut groups: Vec<Vec<usize>> = Vec::with_capacity(self.components);
+        for i in 0..self.len() {
+            let root = self.find(i);
+            if index[root] == usize::MAX {
+                index[root] = groups.len();
+                groups.push(Vec::with_capacity(self.size[root]));
+            }
+            groups[index[root]].push(i);
+        }
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_order() {
+        let mut d = DisjointSet::new();
+        for s in ["a", "b", "c", "d"] {
+            d.push(s.to_string());
+        }
+        let concat = |a: String, b: String| a + &b;
+        // a's payload always comes first, whichever root survives
+        d.union_with(0, 1, concat);
+        assert_eq!(d.get(1), "ab");
+        let root = d.union_with(2, 0, concat);
+        assert_eq!(root, d.find(2));
+        assert_eq!(d.get(0), "cab");
+        assert_eq!(d.set_size(1), 3);
+        // Merging a set with itself doesn't call merge
+        d.union_with(1, 2, |_, _| unreachable!());
+        d.get_mut(3).push('!');
+        assert_eq!(d.get(3), "d!");
+        let mut sets: Vec<_> = d.sets().map(|(_, s)| s.clone()).collect();
+        sets.sort();
+        assert_eq!(sets, ["cab", "d!"]);
+    }
+
+    #[test]
+    fn components_and_groups() {
+        let mut d = DisjointSet::with_len(7);
+        assert_eq!((d.len(), d.components()), (7, 7));
+        assert!(d.union(5, 1));
+        assert!(d.union(1, 3));
+        assert!(!d.union(3, 5));
+        assert!(d.union(6, 4));
+        assert_eq!(d.components(), 4);
+        assert!(d.same_set(3, 5) && !d.same_set(0, 5));
+        assert_eq!(d.groups(), [vec![0], vec![1, 3, 5], vec![2], vec![4, 6]]);
+        assert_eq!(d.sets().count(), 4);
+
+        // A long chain still ends up in one set
+        let mut d = DisjointSet::with_len(1000);
+        for i in 1..1000 {
+            d.union(i - 1, i);
+        }
+        assert_eq!((d.components(), d.set_size(0)), (1, 1000));
+        assert_eq!(d.groups(), [(0..1000).collect::<Vec<_>>()]);
+        assert!(DisjointSet::<u8>::new().groups().is_empty());
+    }
+}
@@ -1,19 +1,131 @@
-use crate::{coord, Coord, FromPrimitive, Input, PrimitiveInt, ToPrimitive};
+use crate::{
+    coord, is_nl, Coord, FieldRender, FromPrimitive, Input, Lines, PrimitiveInt, ToPrimitive,
+};
 use std::{
+    collections::VecDeque,
     iter::StepBy,
     marker::PhantomData,
-    ops::{Index, IndexMut},
+    ops::{Index, IndexMut, Range},
 };
 
-pub trait Field {
+// The shape of a grid and read access to its cells. Field adds the backing slice on top of this;
+// grids that can't hand out a slice, like the bit-packed BitField, only implement Grid, and the
+// helpers here work for both.
+pub trait Grid {
     type Item;
     fn width(&self) -> usize;
     fn height(&self) -> usize;
+
+    // x and y must be in bounds
+    fn cell(&self, x: usize, y: usize) -> &Self::Item;
+
+    fn in_bounds<I: PrimitiveInt + ToPrimitive<usize>>(&self, x: I, y: I) -> bool {
+        x.try_to().is_some_and(|x: usize| x < self.width())
+            && y.try_to().is_some_and(|y: usize| y < self.height())
+    }
+
+    fn in_bounds_tuple<I: PrimitiveInt + ToPrimitive<usize>>(&self, p: (I, I)) -> bool {
+        self.in_bounds(p.0, p.1)
+    }
+
+    fn in_bounds_coord<I: PrimitiveInt + ToPrimitive<usize>>(&self, p: Coord<I>) -> bool {
+        self.in_bounds(p.x, p.y)
+    }
+
+    fn coords<I: FromPrimitive<usize>>(&self) -> impl Iterator<Item = Coord<I>> + 'static {
+        let (w, h) = (self.width(), self.height());
+        (0..h).flat_map(move |y| (0..w).map(move |x| coord(I::from(x), I::from(y))))
+    }
+
+    // Cells within `radius` steps of `center` (which may lie outside of the field), clipped to the field
+    fn manhattan_neighborhood<I>(
+        &self,
+        center: Coord<I>,
+        radius: usize,
+    ) -> impl Iterator<Item = Coord<I>> + 'static
+    where
+        I: PrimitiveInt + ToPrimitive<isize> + FromPrimitive<isize>,
+    {
+        neighborhood(self.width(), self.height(), center, radius, true)
+    }
+
+    fn chebyshev_neighborhood<I>(
+        &self,
+        center: Coord<I>,
+        radius: usize,
+    ) -> impl Iterator<Item = Coord<I>> + 'static
+    where
+        I: PrimitiveInt + ToPrimitive<isize> + FromPrimitive<isize>,
+    {
+        neighborhood(self.width(), self.height(), center, radius, false)
+    }
+
+    // Breadth first distances from all of the sources, moving orthogonally between passable cells.
+    // Cells that can't be reached are set to UNREACHABLE.
+    fn bfs_distances<I: PrimitiveInt + ToPrimitive<usize>>(
+        &self,
+        sources: impl IntoIterator<Item = Coord<I>>,
+        passable: impl Fn(&Self::Item) -> bool,
+    ) -> FieldMutView<'static, u32> {
+        let (w, h) = (self.width(), self.height());
+        let mut dist = FieldMutView::create_with_value(UNREACHABLE, w, w, h);
+        let mut queue = VecDeque::new();
+        for p in sources {
+            let (x, y): (usize, usize) = (p.x.to(), p.y.to());
+            if x < w && y < h && dist[(x, y)] == UNREACHABLE {
+                dist[(x, y)] = 0;
+                queue.push_back((x, y));
+            }
+        }
+
+        while let Some((x, y)) = queue.pop_front() {
+            let d = dist[(x, y)] + 1;
+            let next = [
+                (x.wrapping_sub(1), y),
+                (x + 1, y),
+                (x, y.wrapping_sub(1)),
+                (x, y + 1),
+            ];
+            for (nx, ny) in next {
+                if nx < w && ny < h && dist[(nx, ny)] == UNREACHABLE && passable(self.cell(nx, ny))
+                {
+                    dist[(nx, ny)] = d;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+        dist
+    }
+
+    fn render_cells(&self) -> FieldRender<'_, Self, fn(&Self::Item) -> char>
+    where
+        Self: Sized,
+        Self::Item: Copy + Into<u8>,
+    {
+        FieldRender::new(self, |&c| c.into() as char)
+    }
+
+    fn render(&self) -> FieldRender<'_, Self, fn(&u8) -> char>
+    where
+        Self: Grid<Item = u8> + Sized,
+    {
+        FieldRender::new(self, |&c| c as char)
+    }
+
+    fn render_with<M: Fn(&Self::Item) -> char>(&self, map: M) -> FieldRender<'_, Self, M>
+    where
+        Self: Sized,
+    {
+        FieldRender::new(self, map)
+    }
+}
+
+pub trait Field: Grid {
     fn stride(&self) -> usize;
     fn data(&self) -> &[Self::Item];
 
     fn offset<I: PrimitiveInt + ToPrimitive<usize>>(&self, x: I, y: I) -> usize {
-        y.to() as usize * self.stride() + x.to()
+        y.to() * self.stride() + x.to()
     }
 
     fn tuple_from_offset<I: FromPrimitive<usize>>(&self, o: usize) -> (I, I) {
@@ -27,18 +139,6 @@ pub trait Field {
         self.tuple_from_offset(o).into()
     }
 
-    fn in_bounds<I: PrimitiveInt + ToPrimitive<usize>>(&self, x: I, y: I) -> bool {
-        x.to() < self.width() && y.to() < self.height()
-    }
-
-    fn in_bounds_tuple<I: PrimitiveInt + ToPrimitive<usize>>(&self, p: (I, I)) -> bool {
-        self.in_bounds(p.0, p.1)
-    }
-
-    fn in_bounds_coord<I: PrimitiveInt + ToPrimitive<usize>>(&self, p: Coord<I>) -> bool {
-        self.in_bounds(p.x, p.y)
-    }
-
     fn get<I: PrimitiveInt + ToPrimitive<usize>>(&self, x: I, y: I) -> &Self::Item {
         &self.data()[self.offset(x, y)]
     }
@@ -49,7 +149,7 @@ pub trait Field {
         y: I,
         alt: &'r Self::Item,
     ) -> &'r Self::Item {
-        if x.to() < self.width() && y.to() < self.height() {
+        if self.in_bounds(x, y) {
             &self.data()[self.offset(x, y)]
         } else {
             alt
@@ -66,7 +166,7 @@ pub trait Field {
         &self.data()[o..o + self.width()]
     }
 
-    fn col(&self, index: usize) -> FieldColumn<Self::Item> {
+    fn col(&self, index: usize) -> FieldColumn<'_, Self::Item> {
         FieldColumn {
             data: &self.data()[index..],
             stride: self.stride(),
@@ -74,7 +174,7 @@ pub trait Field {
         }
     }
 
-    fn rows(&self) -> FieldRows<Self::Item> {
+    fn rows(&self) -> FieldRows<'_, Self::Item> {
         FieldRows(FieldView::new(
             self.data(),
             self.width(),
@@ -83,7 +183,7 @@ pub trait Field {
         ))
     }
 
-    fn cols(&self) -> FieldCols<Self::Item> {
+    fn cols(&self) -> FieldCols<'_, Self::Item> {
         FieldCols(FieldView::new(
             self.data(),
             self.width(),
@@ -97,12 +197,129 @@ pub trait Field {
         (0..h).flat_map(move |y| (0..w).map(move |x| y * s + x))
     }
 
-    fn coords<I: FromPrimitive<usize>>(&self) -> impl Iterator<Item = Coord<I>> + 'static {
-        let (w, h) = (self.width(), self.height());
-        (0..h).flat_map(move |y| (0..w).map(move |x| coord(I::from(x), I::from(y))))
+    // The cells of manhattan_neighborhood as one slice per row, along with the coordinate of the first
+    // cell of each. Much cheaper to scan than going through the coordinates one by one.
+    fn manhattan_rows<I>(
+        &self,
+        center: Coord<I>,
+        radius: usize,
+    ) -> impl Iterator<Item = (Coord<I>, &[Self::Item])>
+    where
+        I: PrimitiveInt + ToPrimitive<isize> + FromPrimitive<isize>,
+    {
+        neighborhood_rows(self, center, radius, true)
+    }
+
+    fn chebyshev_rows<I>(
+        &self,
+        center: Coord<I>,
+        radius: usize,
+    ) -> impl Iterator<Item = (Coord<I>, &[Self::Item])>
+    where
+        I: PrimitiveInt + ToPrimitive<isize> + FromPrimitive<isize>,
+    {
+        neighborhood_rows(self, center, radius, false)
+    }
+
+    // Only the cells themselves count, not the stride padding or anything after the last row
+    fn find_unique<I: FromPrimitive<usize>>(&self, item: Self::Item) -> Result<Coord<I>, FindError>
+    where
+        Self::Item: PartialEq,
+    {
+        let mut found = None;
+        for (y, row) in self.rows().enumerate() {
+            for (x, c) in row.iter().enumerate() {
+                if *c != item {
+                    continue;
+                }
+                if let Some(first) = found {
+                    return Err(FindError::Duplicate {
+                        first,
+                        second: coord(x, y),
+                    });
+                }
+                found = Some(coord(x, y));
+            }
+        }
+        found
+            .map(|c| coord(I::from(c.x), I::from(c.y)))
+            .ok_or(FindError::Missing)
+    }
+
+    fn map_cells<C: TryFrom<u8>>(&self) -> Result<FieldMutView<'static, C>, CellError>
+    where
+        Self: Field<Item = u8> + Sized,
+    {
+        let mut cells = Vec::with_capacity(self.width() * self.height());
+        for (y, row) in self.rows().enumerate() {
+            for (x, &c) in row.iter().enumerate() {
+                cells.push(C::try_from(c).map_err(|_| CellError { x, y, byte: c })?);
+            }
+        }
+        Ok(FieldMutView::from_vec(
+            cells,
+            self.width(),
+            self.width(),
+            self.height(),
+        ))
     }
 }
 
+pub const UNREACHABLE: u32 = u32::MAX;
+
+// The clipped run of x per row of a neighborhood, for rows that have any cells in the field
+fn neighborhood_spans<I>(
+    width: usize,
+    height: usize,
+    center: Coord<I>,
+    radius: usize,
+    diamond: bool,
+) -> impl Iterator<Item = (isize, Range<isize>)> + 'static
+where
+    I: PrimitiveInt + ToPrimitive<isize>,
+{
+    let (cx, cy): (isize, isize) = (center.x.to(), center.y.to());
+    let r = radius as isize;
+    let y_range = (cy - r).max(0)..(cy + r + 1).min(height as isize);
+    y_range.filter_map(move |y| {
+        let dx = if diamond { r - (y - cy).abs() } else { r };
+        let x_range = (cx - dx).max(0)..(cx + dx + 1).min(width as isize);
+        (!x_range.is_empty()).then_some((y, x_range))
+    })
+}
+
+fn neighborhood_rows<F, I>(
+    field: &F,
+    center: Coord<I>,
+    radius: usize,
+    diamond: bool,
+) -> impl Iterator<Item = (Coord<I>, &[F::Item])>
+where
+    F: Field + ?Sized,
+    I: PrimitiveInt + ToPrimitive<isize> + FromPrimitive<isize>,
+{
+    let (w, h, s) = (field.width(), field.height(), field.stride());
+    neighborhood_spans(w, h, center, radius, diamond).map(move |(y, xs)| {
+        let o = y as usize * s + xs.start as usize;
+        let row = &field.data()[o..o + xs.len()];
+        (coord(I::from(xs.start), I::from(y)), row)
+    })
+}
+
+fn neighborhood<I>(
+    width: usize,
+    height: usize,
+    center: Coord<I>,
+    radius: usize,
+    diamond: bool,
+) -> impl Iterator<Item = Coord<I>> + 'static
+where
+    I: PrimitiveInt + ToPrimitive<isize> + FromPrimitive<isize>,
+{
+    neighborhood_spans(width, height, center, radius, diamond)
+        .flat_map(|(y, xs)| xs.map(move |x| coord(I::from(x), I::from(y))))
+}
+
 pub trait FieldMut: Field {
     fn data_mut(&mut self) -> &mut [Self::Item];
 
@@ -137,7 +354,7 @@ impl<'a, T> FieldView<'a, T> {
     }
 }
 
-impl<'a, T> Field for FieldView<'a, T> {
+impl<'a, T> Grid for FieldView<'a, T> {
     type Item = T;
 
     fn width(&self) -> usize {
@@ -148,6 +365,12 @@ impl<'a, T> Field for FieldView<'a, T> {
         self.height
     }
 
+    fn cell(&self, x: usize, y: usize) -> &T {
+        &self.data()[self.offset(x, y)]
+    }
+}
+
+impl<'a, T> Field for FieldView<'a, T> {
     fn stride(&self) -> usize {
         self.stride
     }
@@ -184,13 +407,156 @@ impl<'a, T, I: PrimitiveInt + ToPrimitive<usize>> Index<Coord<I>> for FieldView<
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FieldError {
+    Empty,
+    RaggedRow {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    MixedLineEndings {
+        line: usize,
+    },
+}
+
+impl std::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldError::Empty => write!(f, "Field is empty"),
+            FieldError::RaggedRow {
+                line,
+                expected,
+                found,
+            } => write!(f, "Line {line} has width {found}, expected {expected}"),
+            FieldError::MixedLineEndings { line } => write!(
+                f,
+                "Line {line} has a different line ending than the first line"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CellError {
+    pub x: usize,
+    pub y: usize,
+    pub byte: u8,
+}
+
+impl std::fmt::Display for CellError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Invalid cell `{}` at ({}, {})",
+            self.byte.escape_ascii(),
+            self.x,
+            self.y
+        )
+    }
+}
+
+impl std::error::Error for CellError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FindError {
+    Missing,
+    Duplicate {
+        first: Coord<usize>,
+        second: Coord<usize>,
+    },
+}
+
+impl std::fmt::Display for FindError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FindError::Missing => write!(f, "No cell matches"),
+            FindError::Duplicate { first, second } => write!(
+                f,
+                "Cells at ({}, {}) and ({}, {}) both match",
+                first.x, first.y, second.x, second.y
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FindError {}
+
+fn trim_trailing_nl(mut b: &[u8]) -> &[u8] {
+    while let [rest @ .., c] = b {
+        if !is_nl(*c) {
+            break;
+        }
+        b = rest;
+    }
+    b
+}
+
+// Returns (width, stride, height), assuming every row has the same width and line ending
+fn grid_layout(b: &[u8]) -> (usize, usize, usize) {
+    let b = trim_trailing_nl(b);
+    let Some(width) = b.iter().position(|&c| is_nl(c)) else {
+        return (b.len(), b.len(), !b.is_empty() as usize);
+    };
+    let stride = width + 1 + (b[width] == b'\r' && b.get(width + 1) == Some(&b'\n')) as usize;
+    (width, stride, b.len().div_ceil(stride))
+}
+
+fn checked_grid_layout(b: &[u8]) -> Result<(usize, usize, usize), FieldError> {
+    let b = trim_trailing_nl(b);
+    if b.is_empty() {
+        return Err(FieldError::Empty);
+    }
+
+    let (width, stride, _) = grid_layout(b);
+    let nl = &b[width..stride.max(width)];
+    let mut rest = b;
+    let mut height = 0;
+    loop {
+        height += 1;
+        let len = rest.iter().position(|&c| is_nl(c)).unwrap_or(rest.len());
+        if len != width {
+            return Err(FieldError::RaggedRow {
+                line: height,
+                expected: width,
+                found: len,
+            });
+        }
+
+        rest = &rest[len..];
+        if rest.is_empty() {
+            return Ok((width, stride, height));
+        }
+
+        if !rest.starts_with(nl) || (nl == b"\r" && rest.get(1) == Some(&b'\n')) {
+            return Err(FieldError::MixedLineEndings { line: height });
+        }
+        rest = &rest[nl.len()..];
+    }
+}
+
+fn grid_layout_from(b: &[u8]) -> (usize, usize, usize) {
+    #[cfg(feature = "validation")]
+    if let Err(e) = checked_grid_layout(b) {
+        panic!("Validation failed! {e}");
+    }
+
+    grid_layout(b)
+}
+
+impl<'a> FieldView<'a, u8> {
+    pub fn try_from_bytes(b: &'a [u8]) -> Result<Self, FieldError> {
+        let (width, stride, height) = checked_grid_layout(b)?;
+        Ok(Self::new(b, width, stride, height))
+    }
+}
+
 impl<'a> From<&'a [u8]> for FieldView<'a, u8> {
     fn from(input: &'a [u8]) -> Self {
-        let b = input;
-        let width = b.iter().position(|&c| c == b'\r' || c == b'\n').unwrap();
-        let stride = width + 1 + ((b[width] == b'\r') as usize);
-        let height = b.len().div_ceil(stride);
-        Self::new(b, width, stride, height)
+        let (width, stride, height) = grid_layout_from(input);
+        Self::new(input, width, stride, height)
     }
 }
 
@@ -210,6 +576,17 @@ pub struct FieldMutView<'a, T> {
     _ref: PhantomData<&'a mut [T]>,
 }
 
+// Only needed to share fields with the worker threads in parallel.rs
+// SAFETY: FieldMutView either owns its data or borrows it as `&'a mut [T]`, and nothing else can
+// reach it through the raw pointer meanwhile, so moving it to another thread is as safe as moving
+// a `Box<[T]>` or `&mut [T]`
+#[cfg(feature = "parallel")]
+unsafe impl<'a, T: Send> Send for FieldMutView<'a, T> {}
+// SAFETY: Shared access only hands out `&[T]` (data_mut needs `&mut self`), which is what `&[T]`
+// allows across threads when T is Sync
+#[cfg(feature = "parallel")]
+unsafe impl<'a, T: Sync> Sync for FieldMutView<'a, T> {}
+
 impl<'a, T> FieldMutView<'a, T> {
     pub fn from_ref(data: &'a mut [T], width: usize, stride: usize, height: usize) -> Self {
         Self {
@@ -229,7 +606,7 @@ impl<'a, T> FieldMutView<'a, T> {
     {
         if self._owned.is_some() {
             // SAFETY: Since this is an owning FieldMutView, we know that the named lifetime 'a is actually just 'static
-            unsafe { std::mem::transmute(self) }
+            unsafe { std::mem::transmute::<FieldMutView<'a, T>, FieldMutView<'static, T>>(self) }
         } else {
             FieldMutView::create_from_clone(self.data(), self.width, self.stride, self.height)
         }
@@ -237,6 +614,19 @@ impl<'a, T> FieldMutView<'a, T> {
 }
 
 impl<T> FieldMutView<'static, T> {
+    pub fn from_vec(data: Vec<T>, width: usize, stride: usize, height: usize) -> Self {
+        let mut owned = data.into_boxed_slice();
+        Self {
+            data_ptr: owned.as_mut_ptr(),
+            len: owned.len(),
+            width,
+            height,
+            stride,
+            _owned: Some(owned),
+            _ref: PhantomData,
+        }
+    }
+
     pub fn create_from_clone(data: &[T], width: usize, stride: usize, height: usize) -> Self
     where
         T: Clone,
@@ -296,7 +686,7 @@ impl<T> FieldMutView<'static, T> {
     }
 }
 
-impl<'a, T> Field for FieldMutView<'a, T> {
+impl<'a, T> Grid for FieldMutView<'a, T> {
     type Item = T;
 
     fn width(&self) -> usize {
@@ -307,11 +697,17 @@ impl<'a, T> Field for FieldMutView<'a, T> {
         self.height
     }
 
+    fn cell(&self, x: usize, y: usize) -> &T {
+        &self.data()[self.offset(x, y)]
+    }
+}
+
+impl<'a, T> Field for FieldMutView<'a, T> {
     fn stride(&self) -> usize {
         self.stride
     }
 
-    fn data<'s>(&'s self) -> &'s [T] {
+    fn data(&self) -> &[T] {
         unsafe { std::slice::from_raw_parts(self.data_ptr, self.len) }
     }
 }
@@ -364,13 +760,32 @@ impl<'a, T, I: PrimitiveInt + ToPrimitive<usize>> IndexMut<Coord<I>> for FieldMu
     }
 }
 
+impl<'a> FieldMutView<'a, u8> {
+    pub fn try_from_bytes(b: &'a mut [u8]) -> Result<Self, FieldError> {
+        let (width, stride, height) = checked_grid_layout(b)?;
+        Ok(Self::from_ref(b, width, stride, height))
+    }
+}
+
+impl FieldMutView<'static, u8> {
+    pub fn from_ragged(b: &[u8], fill: u8) -> Self {
+        let rows = Lines::new(trim_trailing_nl(b))
+            .map(str::as_bytes)
+            .collect::<Vec<_>>();
+        let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        let mut r = Self::create_with_value(fill, width, width, rows.len());
+        for (y, row) in rows.into_iter().enumerate() {
+            let o = y * width;
+            r.data_mut()[o..o + row.len()].copy_from_slice(row);
+        }
+        r
+    }
+}
+
 impl<'a> From<&'a mut [u8]> for FieldMutView<'a, u8> {
     fn from(input: &'a mut [u8]) -> Self {
-        let b = input;
-        let width = b.iter().position(|&c| c == b'\r' || c == b'\n').unwrap();
-        let stride = width + 1 + ((b[width] == b'\r') as usize);
-        let height = b.len().div_ceil(stride);
-        Self::from_ref(b, width, stride, height)
+        let (width, stride, height) = grid_layout_from(input);
+        Self::from_ref(input, width, stride, height)
     }
 }
 
@@ -389,7 +804,7 @@ impl<'a, T> BorderedFieldView<'a, T> {
     }
 }
 
-impl<'a, T> Field for BorderedFieldView<'a, T> {
+impl<'a, T> Grid for BorderedFieldView<'a, T> {
     type Item = T;
 
     fn width(&self) -> usize {
@@ -400,6 +815,12 @@ impl<'a, T> Field for BorderedFieldView<'a, T> {
         self.view.height
     }
 
+    fn cell(&self, x: usize, y: usize) -> &T {
+        &self.data()[self.offset(x, y)]
+    }
+}
+
+impl<'a, T> Field for BorderedFieldView<'a, T> {
     fn stride(&self) -> usize {
         self.view.stride
     }
@@ -416,7 +837,8 @@ impl<'a, T> Field for BorderedFieldView<'a, T> {
 impl<'a, T, I: PrimitiveInt + ToPrimitive<usize>> Index<I> for BorderedFieldView<'a, T> {
     type Output = T;
     fn index(&self, index: I) -> &Self::Output {
-        self.view.get_by_offset_or(index.to(), &self.border)
+        let (x, y) = self.view.tuple_from_offset::<usize>(index.to());
+        self.get(x, y)
     }
 }
 
@@ -438,6 +860,126 @@ impl<'a, T, I: PrimitiveInt + ToPrimitive<isize> + ToPrimitive<usize>> Index<Coo
     }
 }
 
+pub struct BorderedFieldMutView<'a, T> {
+    view: FieldMutView<'a, T>,
+    border: T,
+    scratch: T,
+}
+
+impl<'a, T: Clone> BorderedFieldMutView<'a, T> {
+    pub fn new(view: FieldMutView<'a, T>, border: T) -> Self {
+        Self {
+            view,
+            scratch: border.clone(),
+            border,
+        }
+    }
+
+    pub fn into_inner(self) -> FieldMutView<'a, T> {
+        self.view
+    }
+
+    pub fn get<I: PrimitiveInt + ToPrimitive<usize>>(&self, x: I, y: I) -> &T {
+        self.view.get_or(x, y, &self.border)
+    }
+
+    // Writes outside of the field end up in a scratch value that is reset on every access
+    pub fn get_mut<I: PrimitiveInt + ToPrimitive<usize>>(&mut self, x: I, y: I) -> &mut T {
+        if self.view.in_bounds(x, y) {
+            self.view.get_mut(x, y)
+        } else {
+            self.scratch.clone_from(&self.border);
+            &mut self.scratch
+        }
+    }
+}
+
+impl<'a, T> Grid for BorderedFieldMutView<'a, T> {
+    type Item = T;
+
+    fn width(&self) -> usize {
+        self.view.width
+    }
+
+    fn height(&self) -> usize {
+        self.view.height
+    }
+
+    fn cell(&self, x: usize, y: usize) -> &T {
+        &self.data()[self.offset(x, y)]
+    }
+}
+
+impl<'a, T> Field for BorderedFieldMutView<'a, T> {
+    fn stride(&self) -> usize {
+        self.view.stride
+    }
+
+    fn data(&self) -> &[T] {
+        self.view.data()
+    }
+
+    fn get<I: PrimitiveInt + ToPrimitive<usize>>(&self, x: I, y: I) -> &Self::Item {
+        self.view.get_or(x, y, &self.border)
+    }
+}
+
+impl<'a, T> FieldMut for BorderedFieldMutView<'a, T> {
+    fn data_mut(&mut self) -> &mut [T] {
+        self.view.data_mut()
+    }
+}
+
+// Offsets index like the (x, y) they stand for, so reads and writes agree on what lies outside the
+// field: offsets in the stride gap or past the end read the border and write to the scratch value,
+// never to the data behind them
+impl<'a, T, I: PrimitiveInt + ToPrimitive<usize>> Index<I> for BorderedFieldMutView<'a, T> {
+    type Output = T;
+    fn index(&self, index: I) -> &Self::Output {
+        let (x, y) = self.view.tuple_from_offset::<usize>(index.to());
+        self.get(x, y)
+    }
+}
+
+impl<'a, T, I: PrimitiveInt + ToPrimitive<usize>> Index<(I, I)> for BorderedFieldMutView<'a, T> {
+    type Output = T;
+    fn index(&self, pos: (I, I)) -> &Self::Output {
+        self.view.get_or(pos.0, pos.1, &self.border)
+    }
+}
+
+impl<'a, T, I: PrimitiveInt + ToPrimitive<usize>> Index<Coord<I>> for BorderedFieldMutView<'a, T> {
+    type Output = T;
+    fn index(&self, pos: Coord<I>) -> &Self::Output {
+        self.view.get_or(pos.x, pos.y, &self.border)
+    }
+}
+
+impl<'a, T: Clone, I: PrimitiveInt + ToPrimitive<usize>> IndexMut<I>
+    for BorderedFieldMutView<'a, T>
+{
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        let (x, y) = self.view.tuple_from_offset::<usize>(index.to());
+        self.get_mut(x, y)
+    }
+}
+
+impl<'a, T: Clone, I: PrimitiveInt + ToPrimitive<usize>> IndexMut<(I, I)>
+    for BorderedFieldMutView<'a, T>
+{
+    fn index_mut(&mut self, pos: (I, I)) -> &mut Self::Output {
+        self.get_mut(pos.0, pos.1)
+    }
+}
+
+impl<'a, T: Clone, I: PrimitiveInt + ToPrimitive<usize>> IndexMut<Coord<I>>
+    for BorderedFieldMutView<'a, T>
+{
+    fn index_mut(&mut self, pos: Coord<I>) -> &mut Self::Output {
+        self.get_mut(pos.x, pos.y)
+    }
+}
+
 #[derive(Clone)]
 pub struct FieldRows<'a, T>(FieldView<'a, T>);
 
@@ -527,6 +1069,10 @@ impl<'a, T> FieldColumn<'a, T> {
     pub fn len(&self) -> usize {
         self.height
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.height == 0
+    }
 }
 
 impl<'a, T> Clone for FieldColumn<'a, T> {
@@ -554,7 +1100,7 @@ impl<'a, T> IntoIterator for FieldColumn<'a, T> {
 
 impl<'a, T: PartialEq> PartialEq for FieldColumn<'a, T> {
     fn eq(&self, other: &Self) -> bool {
-        self.clone().into_iter().eq(other.clone().into_iter())
+        self.clone().into_iter().eq(other.clone())
     }
 }
 
@@ -562,20 +1108,136 @@ impl<'a, T: Eq> Eq for FieldColumn<'a, T> {}
 
 impl<'a, T: PartialOrd> PartialOrd for FieldColumn<'a, T> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.clone()
-            .into_iter()
-            .partial_cmp(other.clone().into_iter())
+        self.clone().into_iter().partial_cmp(other.clone())
     }
 }
 
 impl<'a, T: Ord> Ord for FieldColumn<'a, T> {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.clone().into_iter().cmp(other.clone().into_iter())
+        self.clone().into_iter().cmp(other.clone())
     }
 }
 
 impl<'a, T: std::fmt::Debug> std::fmt::Debug for FieldColumn<'a, T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_list().entries(self.clone().into_iter()).finish()
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PaddedGrid;
+
+    #[test]
+    fn bordered_offsets_match_coords() {
+        let mut bytes = b"ab\ncd\n".to_vec();
+        let mut f = BorderedFieldMutView::new(FieldMutView::from(bytes.as_mut_slice()), b'#');
+        // Offset 2 is the newline between the rows and 6 lies past the end
+        assert_eq!((f[2], f[3], f[6]), (b'#', b'c', b'#'));
+        f[2] = b'x';
+        f[6] = b'y';
+        f[4] = b'z';
+        assert_eq!((f[2], f[6], f[(1, 1)]), (b'#', b'#', b'z'));
+        drop(f);
+        assert_eq!(bytes, b"ab\ncz\n");
+    }
+
+    #[test]
+    fn padded_grid_is_a_grid() {
+        let g = PaddedGrid::new(&FieldView::from(b"a.\n.b\n".as_slice()), 1, b' ');
+        assert_eq!((g.width(), g.height()), (2, 2));
+        assert!(g.in_bounds(1, 1) && !g.in_bounds(-1, 0) && !g.in_bounds(2, 0));
+        assert_eq!(g.render().to_string(), "a.\n.b\n");
+        let dist = g.bfs_distances([coord(0, 0)], |_| true);
+        assert_eq!(dist[(1_usize, 1_usize)], 2);
+    }
+
+    #[test]
+    fn find_unique_skips_padding() {
+        // The second row's newline and the trailing one are not cells
+        let f = FieldView::from(
+            b"ab
+cd
+"
+            .as_slice(),
+        );
+        assert_eq!(f.find_unique::<usize>(b'c'), Ok(coord(0, 1)));
+        assert_eq!(f.find_unique::<usize>(b'\n'), Err(FindError::Missing));
+        let f = FieldView::from(
+            b"ab
+ba
+"
+            .as_slice(),
+        );
+        assert_eq!(
+            f.find_unique::<usize>(b'a'),
+            Err(FindError::Duplicate {
+                first: coord(0, 0),
+                second: coord(1, 1)
+            })
+        );
+    }
+
+    #[test]
+    fn neighborhoods_are_clipped() {
+        let f = FieldView::from(b"abcde\nfghij\nklmno\npqrst\n".as_slice());
+        let n = |c: Coord<i32>, r| f.manhattan_neighborhood(c, r).collect::<Vec<_>>();
+        assert_eq!(
+            n(coord(0, 0), 2),
+            [
+                coord(0, 0),
+                coord(1, 0),
+                coord(2, 0),
+                coord(0, 1),
+                coord(1, 1),
+                coord(0, 2)
+            ]
+        );
+        assert_eq!(n(coord(-2, 1), 2), [coord(0, 1)]);
+        assert_eq!(n(coord(10, 10), 2), []);
+        assert_eq!(
+            f.chebyshev_neighborhood(coord(4, 3), 1).collect::<Vec<_>>(),
+            [coord(3, 2), coord(4, 2), coord(3, 3), coord(4, 3)]
+        );
+
+        let rows = |c: Coord<i32>, r| f.manhattan_rows(c, r).collect::<Vec<_>>();
+        assert_eq!(
+            rows(coord(1, 1), 1),
+            [
+                (coord(1, 0), b"b".as_slice()),
+                (coord(0, 1), b"fgh"),
+                (coord(1, 2), b"l")
+            ]
+        );
+        assert_eq!(
+            rows(coord(4, 0), 2),
+            [
+                (coord(2, 0), b"cde".as_slice()),
+                (coord(3, 1), b"ij"),
+                (coord(4, 2), b"o")
+            ]
+        );
+        assert_eq!(rows(coord(6, 1), 2), [(coord(4, 1), b"j".as_slice())]);
+        assert_eq!(
+            f.chebyshev_rows(coord(-1, -1), 1).collect::<Vec<_>>(),
+            [(coord(0, 0), b"a".as_slice())]
+        );
+    }
+
+    #[test]
+    fn bfs_from_several_sources() {
+        let f = FieldView::from(b".#...\n.#.#.\n...#.\n".as_slice());
+        let u = UNREACHABLE;
+        // A duplicate and an out of bounds source are skipped
+        let sources = [coord(0_usize, 0), coord(4, 0), coord(4, 0), coord(9, 1)];
+        let dist = f.bfs_distances(sources, |&c| c == b'.');
+        assert_eq!(
+            dist.rows().collect::<Vec<_>>(),
+            [[0, u, 2, 1, 0], [1, u, 3, u, 1], [2, 3, 4, u, 2]]
+        );
+        let dist = f.bfs_distances([coord(0_usize, 0)], |&c| c == b'.');
+        assert_eq!(dist.row(0), [0, u, 6, 7, 8]);
+        assert_eq!(f.bfs_distances::<usize>([], |_| true).row(0), [u; 5]);
     }
 }
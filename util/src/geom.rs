@@ -0,0 +1,143 @@
+use crate::{gcd, Coord, Int, Signed, ToPrimitive};
+
+// The componentwise distance between a and b, which doesn't go negative for unsigned T
+fn span<T: Int>(a: Coord<T>, b: Coord<T>) -> Coord<T> {
+    a.component_max(b) - a.component_min(b)
+}
+
+// The smallest lattice step in the direction from a to b, so every lattice point on the line through
+// a and b is a + k * step for some integer k
+pub fn lattice_step<T: Int + Signed>(a: Coord<T>, b: Coord<T>) -> Coord<T> {
+    let d = b - a;
+    let g = gcd(d.x.abs(), d.y.abs());
+    if g == T::default() {
+        d
+    } else {
+        d / g
+    }
+}
+
+// Every lattice point on the segment from a to b, including both ends
+pub fn lattice_points<T: Int + Signed>(a: Coord<T>, b: Coord<T>) -> impl Iterator<Item = Coord<T>> {
+    let d = span(a, b);
+    let steps: i128 = gcd(d.x, d.y).to();
+    lattice_ray(a, lattice_step(a, b)).take(steps as usize + 1)
+}
+
+pub fn lattice_ray<T: Int>(start: Coord<T>, step: Coord<T>) -> impl Iterator<Item = Coord<T>> {
+    std::iter::successors(Some(start), move |&p| Some(p + step))
+}
+
+// All cells a line from a to b passes through when drawn as a connected 8-neighbor line, including
+// both ends
+pub fn bresenham<T: Int + Signed>(a: Coord<T>, b: Coord<T>) -> impl Iterator<Item = Coord<T>> {
+    let d = b - a;
+    let (dx, dy) = (d.x.abs(), -d.y.abs());
+    let step = d.signum();
+    let mut err = dx + dy;
+    let mut cur = Some(a);
+    std::iter::from_fn(move || {
+        let p = cur?;
+        cur = (p != b).then(|| {
+            let mut n = p;
+            let e2 = err + err;
+            if e2 >= dy {
+                err = err + dy;
+                n.x = n.x + step.x;
+            }
+            if e2 <= dx {
+                err = err + dx;
+                n.y = n.y + step.y;
+            }
+            n
+        });
+        Some(p)
+    })
+}
+
+fn edges<T: Copy>(poly: &[Coord<T>]) -> impl Iterator<Item = (Coord<T>, Coord<T>)> + '_ {
+    poly.iter()
+        .zip(poly.iter().cycle().skip(1))
+        .map(|(&a, &b)| (a, b))
+}
+
+// Twice the signed area of a simple polygon, positive if the vertices go clockwise on screen
+pub fn signed_area2<T: Int + Signed>(poly: &[Coord<T>]) -> T {
+    edges(poly).fold(T::default(), |acc, (a, b)| acc + a.cross(b))
+}
+
+// Rounds down if the polygon has an odd doubled area
+pub fn area<T: Int + Signed>(poly: &[Coord<T>]) -> T {
+    signed_area2(poly).abs() / T::from(2_i128)
+}
+
+// The number of lattice points on the edges of the polygon
+pub fn boundary_points<T: Int>(poly: &[Coord<T>]) -> T {
+    edges(poly).fold(T::default(), |acc, (a, b)| {
+        let d = span(a, b);
+        acc + gcd(d.x, d.y)
+    })
+}
+
+// The number of lattice points strictly inside the polygon, using Pick's theorem: A = I + B/2 - 1
+pub fn interior_points<T: Int + Signed>(poly: &[Coord<T>]) -> T {
+    let two = T::from(2_i128);
+    (signed_area2(poly).abs() - boundary_points(poly) + two) / two
+}
+
+// Interior and boundary points together, which is the number of cells covered when the polygon is
+// traced through cell centers
+pub fn covered_points<T: Int + Signed>(poly: &[Coord<T>]) -> T {
+    interior_points(poly) + boundary_points(poly)
+}
+
+pub fn perimeter<T: Int + ToPrimitive<f64>>(poly: &[Coord<T>]) -> f64 {
+    edges(poly)
+        .map(|(a, b)| {
+            let d = span(a, b);
+            let (x, y): (f64, f64) = (d.x.to(), d.y.to());
+            x.hypot(y)
+        })
+        .sum()
+}
+
+// The perimeter of a polygon with only horizontal and vertical edges, in exact integer arithmetic
+pub fn rectilinear_perimeter<T: Int>(poly: &[Coord<T>]) -> T {
+    edges(poly).fold(T::default(), |acc, (a, b)| acc + a.manhattan(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn polygons() {
+        let square = [coord(0, 0), coord(4, 0), coord(4, 4), coord(0, 4)];
+        assert_eq!(signed_area2(&square), 32);
+        assert_eq!(area(&square), 16);
+        assert_eq!(boundary_points(&square), 16);
+        assert_eq!(interior_points(&square), 9);
+        assert_eq!(covered_points(&square), 25);
+
+        // The sign free functions also take unsigned coordinates, whichever way the edges point
+        let square = square.map(|c| c.to::<u32>());
+        assert_eq!(boundary_points(&square), 16);
+        assert_eq!(rectilinear_perimeter(&square), 16);
+        assert_eq!(perimeter(&square), 16.0);
+    }
+
+    #[test]
+    fn lattice() {
+        assert_eq!(lattice_step(coord(1, 1), coord(7, -8)), coord(2, -3));
+        let points: Vec<_> = lattice_points(coord(7, -8), coord(1, 1)).collect();
+        assert_eq!(
+            points,
+            [coord(7, -8), coord(5, -5), coord(3, -2), coord(1, 1)]
+        );
+        let ray: Vec<_> = lattice_ray(coord(1u8, 2), coord(3, 0)).take(3).collect();
+        assert_eq!(ray, [coord(1, 2), coord(4, 2), coord(7, 2)]);
+        let line: Vec<_> = bresenham(coord(0, 0), coord(3, 1)).collect();
+        assert_eq!(line, [coord(0, 0), coord(1, 0), coord(2, 1), coord(3, 1)]);
+    }
+}
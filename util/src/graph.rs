@@ -0,0 +1,510 @@
+use crate::{DisjointSet, FastHashMap};
+use std::{collections::VecDeque, fmt, hash::Hash};
+
+// Maps names of any kind to dense node indices 0..len in order of first appearance
+#[derive(Clone, Debug)]
+pub struct Interner<K> {
+    ids: FastHashMap<K, usize>,
+    names: Vec<K>,
+}
+
+impl<K: Hash + Eq + Clone> Interner<K> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            ids: FastHashMap::with_capacity_and_hasher(capacity, Default::default()),
+            names: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn intern(&mut self, name: K) -> usize {
+        let next = self.names.len();
+        *self.ids.entry(name).or_insert_with_key(|name| {
+            self.names.push(name.clone());
+            next
+        })
+    }
+
+    pub fn get(&self, name: &K) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: usize) -> &K {
+        &self.names[id]
+    }
+
+    pub fn names(&self) -> &[K] {
+        &self.names
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+impl<K: Hash + Eq + Clone> Default for Interner<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// A directed graph in compressed sparse row form. The neighbors of every node are sorted and free of
+// duplicates. Undirected graphs store every edge in both directions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Graph {
+    offsets: Vec<usize>,
+    targets: Vec<usize>,
+}
+
+impl Graph {
+    pub fn from_edges(len: usize, edges: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        let mut edges: Vec<_> = edges.into_iter().collect();
+        edges.sort_unstable();
+        edges.dedup();
+
+        let mut offsets = vec![0; len + 1];
+        for &(a, b) in &edges {
+            assert!(
+                a < len && b < len,
+                "Edge ({a}, {b}) out of range for {len} nodes"
+            );
+            offsets[a + 1] += 1;
+        }
+        for i in 0..len {
+            offsets[i + 1] += offsets[i];
+        }
+        Self {
+            offsets,
+            targets: edges.into_iter().map(|(_, b)| b).collect(),
+        }
+    }
+
+    pub fn from_undirected_edges(
+        len: usize,
+        edges: impl IntoIterator<Item = (usize, usize)>,
+    ) -> Self {
+        Self::from_edges(len, edges.into_iter().flat_map(|(a, b)| [(a, b), (b, a)]))
+    }
+
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.targets.len()
+    }
+
+    pub fn neighbors(&self, node: usize) -> &[usize] {
+        &self.targets[self.offsets[node]..self.offsets[node + 1]]
+    }
+
+    pub fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.neighbors(from).binary_search(&to).is_ok()
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.len()).flat_map(move |a| self.neighbors(a).iter().map(move |&b| (a, b)))
+    }
+
+    pub fn reversed(&self) -> Self {
+        Self::from_edges(self.len(), self.edges().map(|(a, b)| (b, a)))
+    }
+
+    // The subgraph between the given nodes, where node i of the result is nodes[i]
+    pub fn induced(&self, nodes: &[usize]) -> Self {
+        let mut local = vec![usize::MAX; self.len()];
+        for (i, &n) in nodes.iter().enumerate() {
+            local[n] = i;
+        }
+        let local = &local;
+        let edges = nodes.iter().enumerate().flat_map(|(i, &n)| {
+            self.neighbors(n)
+                .iter()
+                .filter(move |&&m| local[m] != usize::MAX)
+                .map(move |&m| (i, local[m]))
+        });
+        Self::from_edges(nodes.len(), edges)
+    }
+
+    // Every triangle of an undirected graph once, as [a, b, c] with a < b < c
+    pub fn triangles(&self) -> impl Iterator<Item = [usize; 3]> + '_ {
+        (0..self.len()).flat_map(move |a| {
+            let above = |n: usize| move |&&m: &&usize| m > n;
+            self.neighbors(a)
+                .iter()
+                .filter(above(a))
+                .flat_map(move |&b| {
+                    self.neighbors(b)
+                        .iter()
+                        .filter(above(b))
+                        .filter(move |&&c| self.has_edge(a, c))
+                        .map(move |&c| [a, b, c])
+                })
+        })
+    }
+}
+
+fn bits_iter(bits: &[u64]) -> impl Iterator<Item = usize> + '_ {
+    bits.iter().enumerate().flat_map(|(i, &w)| {
+        std::iter::successors((w != 0).then_some(w), |&w| {
+            let w = w & (w - 1);
+            (w != 0).then_some(w)
+        })
+        .map(move |w| i * 64 + w.trailing_zeros() as usize)
+    })
+}
+
+fn bits_count(bits: &[u64]) -> usize {
+    bits.iter().map(|w| w.count_ones() as usize).sum()
+}
+
+fn bits_and(a: &[u64], b: &[u64]) -> Vec<u64> {
+    a.iter().zip(b).map(|(a, b)| a & b).collect()
+}
+
+// The same graph as an adjacency matrix of bits, for dense set operations on neighborhoods. Self-loops
+// are dropped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitGraph {
+    len: usize,
+    words: usize,
+    bits: Vec<u64>,
+}
+
+impl BitGraph {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.row(from)[to / 64] & (1 << (to % 64)) != 0
+    }
+
+    // The neighbors of a node as a bitset, where bit n of word n / 64 is node n
+    pub fn row(&self, node: usize) -> &[u64] {
+        &self.bits[node * self.words..(node + 1) * self.words]
+    }
+}
+
+impl From<&Graph> for BitGraph {
+    fn from(g: &Graph) -> Self {
+        let words = g.len().div_ceil(64);
+        let mut bits = vec![0; g.len() * words];
+        // Self-loops are left out: a node in its own neighborhood would survive every intersection
+        // with it, and max_clique would then pick it twice
+        for (a, b) in g.edges().filter(|(a, b)| a != b) {
+            bits[a * words + b / 64] |= 1 << (b % 64);
+        }
+        Self {
+            len: g.len(),
+            words,
+            bits,
+        }
+    }
+}
+
+// A largest clique of an undirected graph, sorted by node index. Bron-Kerbosch with pivoting on
+// bitsets, pruning branches that can't beat the best clique found so far.
+pub fn max_clique(g: &Graph) -> Vec<usize> {
+    fn expand(
+        g: &BitGraph,
+        r: &mut Vec<usize>,
+        mut p: Vec<u64>,
+        mut x: Vec<u64>,
+        best: &mut Vec<usize>,
+    ) {
+        let candidates = bits_count(&p);
+        if candidates == 0 {
+            if bits_count(&x) == 0 && r.len() > best.len() {
+                best.clone_from(r);
+            }
+            return;
+        }
+        if r.len() + candidates <= best.len() {
+            return;
+        }
+
+        // Neighbors of the pivot are covered by the branch that picks the pivot or one of its neighbors
+        let union: Vec<u64> = p.iter().zip(&x).map(|(p, x)| p | x).collect();
+        let pivot = bits_iter(&union)
+            .max_by_key(|&u| bits_count(&bits_and(&p, g.row(u))))
+            .unwrap();
+        let branches: Vec<usize> = bits_iter(&p).filter(|&v| !g.has_edge(pivot, v)).collect();
+
+        for v in branches {
+            r.push(v);
+            expand(g, r, bits_and(&p, g.row(v)), bits_and(&x, g.row(v)), best);
+            r.pop();
+            p[v / 64] &= !(1 << (v % 64));
+            x[v / 64] |= 1 << (v % 64);
+        }
+    }
+
+    let bg = BitGraph::from(g);
+    let mut all = vec![0_u64; bg.words];
+    for v in 0..g.len() {
+        all[v / 64] |= 1 << (v % 64);
+    }
+    let mut best = Vec::new();
+    expand(&bg, &mut Vec::new(), all, vec![0; bg.words], &mut best);
+    best.sort_unstable();
+    best
+}
+
+// Components of the graph with edge directions ignored, each sorted, in order of their smallest node
+pub fn connected_components(g: &Graph) -> Vec<Vec<usize>> {
+    let mut sets = DisjointSet::with_len(g.len());
+    for (a, b) in g.edges() {
+        sets.union(a, b);
+    }
+    sets.groups()
+}
+
+// Tarjan's algorithm without recursion. Components come out in reverse topological order, so no
+// edge leads from a component to one that appears after it.
+pub fn strongly_connected_components(g: &Graph) -> Vec<Vec<usize>> {
+    const UNVISITED: usize = usize::MAX;
+    let n = g.len();
+    let mut index = vec![UNVISITED; n];
+    let mut low = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    let mut components = Vec::new();
+    let mut counter = 0;
+
+    for start in 0..n {
+        if index[start] != UNVISITED {
+            continue;
+        }
+        // Each frame is a node and the position of the next edge to follow
+        let mut frames = vec![(start, 0)];
+        index[start] = counter;
+        low[start] = counter;
+        counter += 1;
+        stack.push(start);
+        on_stack[start] = true;
+
+        while let Some(&(v, edge)) = frames.last() {
+            if let Some(&w) = g.neighbors(v).get(edge) {
+                frames.last_mut().unwrap().1 += 1;
+                if index[w] == UNVISITED {
+                    index[w] = counter;
+                    low[w] = counter;
+                    counter += 1;
+                    stack.push(w);
+                    on_stack[w] = true;
+                    frames.push((w, 0));
+                } else if on_stack[w] {
+                    low[v] = low[v].min(index[w]);
+                }
+                continue;
+            }
+
+            frames.pop();
+            if let Some(&(parent, _)) = frames.last() {
+                low[parent] = low[parent].min(low[v]);
+            }
+            if low[v] == index[v] {
+                let mut component = Vec::new();
+                loop {
+                    let w = stack.pop().unwrap();
+                    on_stack[w] = false;
+                    component.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                component.sort_unstable();
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CycleError {
+    // The nodes of one cycle in edge order, the last one leading back to the first
+    pub cycle: Vec<usize>,
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Graph has a cycle through nodes {:?}", self.cycle)
+    }
+}
+
+impl std::error::Error for CycleError {}
+
+// Kahn's algorithm, or one of the cycles that make a topological order impossible
+pub fn topo_sort(g: &Graph) -> Result<Vec<usize>, CycleError> {
+    let n = g.len();
+    let mut indegree = vec![0; n];
+    for (_, b) in g.edges() {
+        indegree[b] += 1;
+    }
+
+    let mut queue: VecDeque<usize> = (0..n).filter(|&v| indegree[v] == 0).collect();
+    let mut order = Vec::with_capacity(n);
+    while let Some(v) = queue.pop_front() {
+        order.push(v);
+        for &w in g.neighbors(v) {
+            indegree[w] -= 1;
+            if indegree[w] == 0 {
+                queue.push_back(w);
+            }
+        }
+    }
+    if order.len() == n {
+        return Ok(order);
+    }
+
+    // Every node left over has a predecessor that is also left over, so walking backwards along
+    // those has to run into a cycle eventually
+    let reversed = g.reversed();
+    let mut seen = vec![usize::MAX; n];
+    let mut path = Vec::new();
+    let mut v = (0..n).find(|&v| indegree[v] > 0).unwrap();
+    while seen[v] == usize::MAX {
+        seen[v] = path.len();
+        path.push(v);
+        v = *reversed
+            .neighbors(v)
+            .iter()
+            .find(|&&u| indegree[u] > 0)
+            .unwrap();
+    }
+    let mut cycle = path.split_off(seen[v]);
+    cycle.reverse();
+    Err(CycleError { cycle })
+}
+
+// The smallest graph with the same reachability as the given acyclic graph
+pub fn transitive_reduction(g: &Graph) -> Result<Graph, CycleError> {
+    let order = topo_sort(g)?;
+    let n = g.len();
+    let mut position = vec![0; n];
+    for (i, &v) in order.iter().enumerate() {
+        position[v] = i;
+    }
+
+    // reach[v] holds everything reachable from v in one or more steps
+    let words = n.div_ceil(64);
+    let mut reach = vec![0_u64; n * words];
+    let mut kept = Vec::with_capacity(g.edge_count());
+    for &v in order.iter().rev() {
+        let mut successors = g.neighbors(v).to_vec();
+        successors.sort_unstable_by_key(|&w| position[w]);
+
+        // A successor can only be reached through another successor that comes earlier in the order
+        let mut acc = vec![0_u64; words];
+        for w in successors {
+            if acc[w / 64] & (1 << (w % 64)) == 0 {
+                kept.push((v, w));
+            }
+            acc[w / 64] |= 1 << (w % 64);
+            for (a, r) in acc.iter_mut().zip(&reach[w * words..(w + 1) * words]) {
+                *a |= r;
+            }
+        }
+        reach[v * words..(v + 1) * words].copy_from_slice(&acc);
+    }
+    Ok(Graph::from_edges(n, kept))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cliques() {
+        // A 4-clique, a triangle hanging off it, and a self-loop inside the triangle
+        let edges = [
+            (0, 1),
+            (0, 2),
+            (0, 3),
+            (1, 2),
+            (1, 3),
+            (2, 3),
+            (3, 4),
+            (4, 5),
+            (5, 6),
+            (4, 6),
+        ];
+        let g = Graph::from_undirected_edges(7, edges.into_iter().chain([(5, 5)]));
+        assert_eq!(max_clique(&g), [0, 1, 2, 3]);
+        assert_eq!(g.triangles().count(), 5);
+
+        assert_eq!(max_clique(&Graph::from_undirected_edges(1, [(0, 0)])), [0]);
+        let g = Graph::from_undirected_edges(3, [(0, 0), (0, 1), (1, 1), (1, 2)]);
+        assert_eq!(max_clique(&g), [0, 1]);
+        assert!(!BitGraph::from(&g).has_edge(1, 1));
+        assert_eq!(max_clique(&Graph::from_edges(0, [])), Vec::<usize>::new());
+
+        // Spread over several words of the bitsets
+        let big: Vec<_> = (60..70).collect();
+        let edges = big
+            .iter()
+            .flat_map(|&a| big.iter().filter(move |&&b| b > a).map(move |&b| (a, b)))
+            .chain((0..129).map(|a| (a, a + 1)));
+        assert_eq!(max_clique(&Graph::from_undirected_edges(130, edges)), big);
+    }
+
+    #[test]
+    fn components() {
+        let edges = [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 3), (6, 6)];
+        let g = Graph::from_edges(7, edges);
+        assert_eq!(
+            strongly_connected_components(&g),
+            [vec![3, 4], vec![0, 1, 2], vec![5], vec![6]]
+        );
+        assert_eq!(
+            connected_components(&g),
+            [vec![0, 1, 2, 3, 4], vec![5], vec![6]]
+        );
+    }
+
+    #[test]
+    fn topological_order() {
+        let g = Graph::from_edges(6, [(5, 2), (5, 0), (4, 0), (4, 1), (2, 3), (3, 1)]);
+        assert_eq!(topo_sort(&g), Ok(vec![4, 5, 0, 2, 3, 1]));
+
+        // The cycle 1 -> 2 -> 3 -> 1 keeps 4 from being ordered too, but only the cycle is reported
+        let g = Graph::from_edges(5, [(0, 1), (1, 2), (2, 3), (3, 1), (3, 4)]);
+        assert_eq!(
+            topo_sort(&g),
+            Err(CycleError {
+                cycle: vec![2, 3, 1]
+            })
+        );
+        let g = Graph::from_edges(2, [(0, 1), (1, 1)]);
+        assert_eq!(topo_sort(&g), Err(CycleError { cycle: vec![1] }));
+    }
+
+    #[test]
+    fn reduction() {
+        let g = Graph::from_edges(5, [(0, 1), (1, 2), (0, 2), (2, 3), (0, 3), (1, 3), (4, 3)]);
+        let expected = Graph::from_edges(5, [(0, 1), (1, 2), (2, 3), (4, 3)]);
+        assert_eq!(transitive_reduction(&g), Ok(expected));
+        let g = Graph::from_edges(2, [(0, 1), (1, 0)]);
+        assert_eq!(
+            transitive_reduction(&g),
+            Err(CycleError { cycle: vec![1, 0] })
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{BuildHasherDefault, Hasher},
+};
+
+// A small and fast non-cryptographic hasher in the style of FxHash. Good enough for coordinates and
+// grid contents, not meant to hold up against adversarial input.
+#[derive(Default, Clone, Copy)]
+pub struct FxHasher(u64);
+
+pub type FastBuildHasher = BuildHasherDefault<FxHasher>;
+pub type FastHashMap<K, V> = HashMap<K, V, FastBuildHasher>;
+pub type FastHashSet<K> = HashSet<K, FastBuildHasher>;
+
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+impl Hasher for FxHasher {
+    fn finish(&self) -> u64 {
+        // The multiply only carries entropy upwards, but HashMap picks buckets with the low bits
+        self.0.rotate_left(26)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for c in &mut chunks {
+            self.write_u64(u64::from_le_bytes(c.try_into().unwrap()));
+        }
+        for &b in chunks.remainder() {
+            self.write_u64(b as u64);
+        }
+    }
+
+    fn write_u8(&mut self, v: u8) {
+        self.write_u64(v as u64);
+    }
+
+    fn write_u16(&mut self, v: u16) {
+        self.write_u64(v as u64);
+    }
+
+    fn write_u32(&mut self, v: u32) {
+        self.write_u64(v as u64);
+    }
+
+    fn write_i32(&mut self, v: i32) {
+        self.write_u64(v as u32 as u64);
+    }
+
+    fn write_usize(&mut self, v: usize) {
+        self.write_u64(v as u64);
+    }
+
+    fn write_u64(&mut self, v: u64) {
+        self.0 = (self.0.rotate_left(5) ^ v).wrapping_mul(SEED);
+    }
+}
+
+// Lets Coord hash both components with a single hasher round where they fit into 64 bits together
+pub trait HashPair: Sized {
+    fn hash_pair<H: Hasher>(a: &Self, b: &Self, state: &mut H);
+}
+
+macro_rules! impl_hash_pair_packed {
+    ($($t:ty => $u:ty),+) => {
+        $(
+            impl HashPair for $t {
+                fn hash_pair<H: Hasher>(a: &Self, b: &Self, state: &mut H) {
+                    state.write_u64((*a as $u as u64) << 32 | *b as $u as u64);
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! impl_hash_pair {
+    ($($t:ty),+) => {
+        $(
+            impl HashPair for $t {
+                fn hash_pair<H: Hasher>(a: &Self, b: &Self, state: &mut H) {
+                    std::hash::Hash::hash(a, state);
+                    std::hash::Hash::hash(b, state);
+                }
+            }
+        )+
+    };
+}
+
+impl_hash_pair_packed!(i8 => u8, i16 => u16, i32 => u32, u8 => u8, u16 => u16, u32 => u32);
+impl_hash_pair!(i64, i128, isize, u64, u128, usize);
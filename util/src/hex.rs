@@ -0,0 +1,185 @@
+use std::ops::*;
+
+// Axial hex coordinates. The implicit third cube coordinate is s = -q - r. Directions follow the
+// pointy-top layout: +q is east, +r is south-east.
+#[derive(Default, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
+pub struct HexCoord {
+    pub q: i32,
+    pub r: i32,
+}
+
+pub const fn hex(q: i32, r: i32) -> HexCoord {
+    HexCoord { q, r }
+}
+
+impl HexCoord {
+    // East, north-east, north-west, west, south-west, south-east
+    pub const DIRECTIONS: [HexCoord; 6] = [
+        hex(1, 0),
+        hex(1, -1),
+        hex(0, -1),
+        hex(-1, 0),
+        hex(-1, 1),
+        hex(0, 1),
+    ];
+
+    pub fn new(q: i32, r: i32) -> Self {
+        Self { q, r }
+    }
+
+    pub fn from_cube(q: i32, r: i32, s: i32) -> Self {
+        debug_assert_eq!(q + r + s, 0);
+        Self { q, r }
+    }
+
+    pub fn s(self) -> i32 {
+        -self.q - self.r
+    }
+
+    pub fn cube(self) -> (i32, i32, i32) {
+        (self.q, self.r, self.s())
+    }
+
+    pub fn neighbors6(self) -> [Self; 6] {
+        Self::DIRECTIONS.map(|d| self + d)
+    }
+
+    pub fn distance(self, other: Self) -> i32 {
+        let d = self - other;
+        (d.q.abs() + d.r.abs() + d.s().abs()) / 2
+    }
+
+    // Rotations by 60 degrees around the origin
+    pub fn rotate_cw(self) -> Self {
+        let (q, r, s) = self.cube();
+        Self::from_cube(-r, -s, -q)
+    }
+
+    pub fn rotate_ccw(self) -> Self {
+        let (q, r, s) = self.cube();
+        Self::from_cube(-s, -q, -r)
+    }
+
+    pub fn rotate_around(self, center: Self, steps_cw: i32) -> Self {
+        let mut d = self - center;
+        for _ in 0..steps_cw.rem_euclid(6) {
+            d = d.rotate_cw();
+        }
+        center + d
+    }
+
+    // All cells at exactly `radius` steps, starting south-west of self and going around counter-clockwise
+    pub fn ring(self, radius: i32) -> impl Iterator<Item = Self> {
+        let start = self + Self::DIRECTIONS[4] * radius;
+        let count = if radius == 0 { 1 } else { 6 * radius as usize };
+        (0..count).scan(start, move |cur, i| {
+            let r = *cur;
+            *cur += Self::DIRECTIONS[i / radius.max(1) as usize % 6];
+            Some(r)
+        })
+    }
+}
+
+impl Neg for HexCoord {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        hex(-self.q, -self.r)
+    }
+}
+
+impl Add for HexCoord {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        hex(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl AddAssign for HexCoord {
+    fn add_assign(&mut self, rhs: Self) {
+        self.q += rhs.q;
+        self.r += rhs.r;
+    }
+}
+
+impl Sub for HexCoord {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        hex(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl SubAssign for HexCoord {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.q -= rhs.q;
+        self.r -= rhs.r;
+    }
+}
+
+impl Mul<i32> for HexCoord {
+    type Output = Self;
+    fn mul(self, rhs: i32) -> Self::Output {
+        hex(self.q * rhs, self.r * rhs)
+    }
+}
+
+impl std::fmt::Display for HexCoord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {}, {})", self.q, self.r, self.s())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbors_and_distance() {
+        let p = hex(2, -3);
+        for n in p.neighbors6() {
+            assert_eq!(p.distance(n), 1);
+            assert_eq!(n.q + n.r + n.s(), p.q + p.r + p.s());
+        }
+        assert_eq!(hex(0, 0).distance(hex(3, -1)), 3);
+        assert_eq!(hex(3, -1).distance(hex(0, 0)), 3);
+        assert_eq!(hex(-2, 4).distance(hex(1, -2)), 6);
+        assert_eq!(HexCoord::from_cube(1, 2, -3).cube(), (1, 2, -3));
+        assert_eq!(hex(1, 2).to_string(), "(1, 2, -3)");
+    }
+
+    #[test]
+    fn rotations() {
+        // East turns south-east
+        assert_eq!(hex(1, 0).rotate_cw(), hex(0, 1));
+        assert_eq!(hex(0, 1).rotate_ccw(), hex(1, 0));
+        let p = hex(3, -1);
+        let mut q = p;
+        for _ in 0..6 {
+            q = q.rotate_cw();
+            assert_eq!(q.distance(hex(0, 0)), 3);
+        }
+        assert_eq!(q, p);
+        let center = hex(-1, 2);
+        assert_eq!(p.rotate_around(center, 2), p.rotate_around(center, -4));
+        assert_eq!(p.rotate_around(center, 6), p);
+    }
+
+    #[test]
+    fn rings() {
+        let c = hex(1, 1);
+        assert_eq!(c.ring(0).collect::<Vec<_>>(), [c]);
+        // The first ring is the neighbours, starting south-west
+        let ring: Vec<_> = c.ring(1).collect();
+        assert_eq!(ring[0], c + hex(-1, 1));
+        let mut sorted = ring.clone();
+        let mut neighbors = c.neighbors6();
+        sorted.sort();
+        neighbors.sort();
+        assert_eq!(sorted, neighbors);
+
+        let ring: Vec<_> = c.ring(3).collect();
+        assert_eq!(ring.len(), 18);
+        assert!(ring.iter().all(|&p| p.distance(c) == 3));
+        assert!(ring.windows(2).all(|w| w[0].distance(w[1]) == 1));
+        assert_eq!(ring[17].distance(ring[0]), 1);
+    }
+}
@@ -0,0 +1,686 @@
+use crate::{Coord, Field, FieldMut, Grid, PrimitiveInt, ToPrimitive};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    ops::{Index, IndexMut},
+    path::{Path, PathBuf},
+};
+
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const WHITE: Rgb = [255, 255, 255];
+
+pub fn gray(v: u8) -> Rgb {
+    [v, v, v]
+}
+
+pub fn luma(c: Rgb) -> u8 {
+    ((c[0] as u32 * 299 + c[1] as u32 * 587 + c[2] as u32 * 114) / 1000) as u8
+}
+
+#[derive(Clone)]
+pub struct Palette {
+    table: [Rgb; 256],
+}
+
+impl Palette {
+    pub fn new(default: Rgb) -> Self {
+        Self {
+            table: [default; 256],
+        }
+    }
+
+    pub fn with(mut self, c: u8, color: Rgb) -> Self {
+        self.table[c as usize] = color;
+        self
+    }
+
+    pub fn get(&self, c: u8) -> Rgb {
+        self.table[c as usize]
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::new(BLACK)
+            .with(b'#', WHITE)
+            .with(b'O', [255, 200, 0])
+            .with(b'[', [255, 200, 0])
+            .with(b']', [255, 200, 0])
+            .with(b'@', [255, 0, 0])
+            .with(b'S', [0, 255, 0])
+            .with(b'E', [0, 128, 255])
+    }
+}
+
+#[derive(Clone)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, fill: Rgb) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![fill; width * height],
+        }
+    }
+
+    pub fn from_field<F: Field + ?Sized>(field: &F, palette: impl Fn(&F::Item) -> Rgb) -> Self {
+        let pixels = field.rows().flat_map(|r| r.iter().map(&palette)).collect();
+        Self {
+            width: field.width(),
+            height: field.height(),
+            pixels,
+        }
+    }
+
+    pub fn from_bytes<F: Field<Item = u8> + ?Sized>(field: &F, palette: &Palette) -> Self {
+        Self::from_field(field, |&c| palette.get(c))
+    }
+
+    pub fn scaled(&self, factor: usize) -> Self {
+        if factor == 1 {
+            return self.clone();
+        }
+
+        let width = self.width * factor;
+        let mut pixels = Vec::with_capacity(width * self.height * factor);
+        for row in self.pixels.chunks(self.width.max(1)) {
+            let start = pixels.len();
+            pixels.extend(row.iter().flat_map(|&p| std::iter::repeat_n(p, factor)));
+            for _ in 1..factor {
+                pixels.extend_from_within(start..start + width);
+            }
+        }
+        Self {
+            width,
+            height: self.height * factor,
+            pixels,
+        }
+    }
+
+    pub fn write_ppm(&self, mut w: impl Write) -> io::Result<()> {
+        write!(w, "P6\n{} {}\n255\n", self.width, self.height)?;
+        w.write_all(self.pixels.as_flattened())
+    }
+
+    pub fn write_pgm(&self, mut w: impl Write) -> io::Result<()> {
+        write!(w, "P5\n{} {}\n255\n", self.width, self.height)?;
+        let gray = self.pixels.iter().map(|&c| luma(c)).collect::<Vec<_>>();
+        w.write_all(&gray)
+    }
+
+    pub fn write_png(&self, mut w: impl Write) -> io::Result<()> {
+        let mut raw = Vec::with_capacity((self.width * 3 + 1) * self.height);
+        for row in self.pixels.chunks(self.width.max(1)) {
+            raw.push(0);
+            raw.extend_from_slice(row.as_flattened());
+        }
+
+        let mut ihdr = Vec::with_capacity(13);
+        ihdr.extend_from_slice(&(self.width as u32).to_be_bytes());
+        ihdr.extend_from_slice(&(self.height as u32).to_be_bytes());
+        ihdr.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+        w.write_all(b"\x89PNG\r\n\x1a\n")?;
+        write_png_chunk(&mut w, b"IHDR", &ihdr)?;
+        write_png_chunk(&mut w, b"IDAT", &zlib_stored(&raw))?;
+        write_png_chunk(&mut w, b"IEND", &[])
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let mut w = BufWriter::new(File::create(path)?);
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("ppm") => self.write_ppm(&mut w)?,
+            Some("pgm") => self.write_pgm(&mut w)?,
+            Some("png") => self.write_png(&mut w)?,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Unsupported image format: {}", path.display()),
+                ))
+            }
+        }
+        w.flush()
+    }
+}
+
+impl Grid for Image {
+    type Item = Rgb;
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn cell(&self, x: usize, y: usize) -> &Rgb {
+        &self.data()[self.offset(x, y)]
+    }
+}
+
+impl Field for Image {
+    fn stride(&self) -> usize {
+        self.width
+    }
+
+    fn data(&self) -> &[Rgb] {
+        &self.pixels
+    }
+}
+
+impl FieldMut for Image {
+    fn data_mut(&mut self) -> &mut [Rgb] {
+        &mut self.pixels
+    }
+}
+
+impl<I: PrimitiveInt + ToPrimitive<usize>> Index<Coord<I>> for Image {
+    type Output = Rgb;
+    fn index(&self, pos: Coord<I>) -> &Self::Output {
+        &self.pixels[self.offset(pos.x, pos.y)]
+    }
+}
+
+impl<I: PrimitiveInt + ToPrimitive<usize>> IndexMut<Coord<I>> for Image {
+    fn index_mut(&mut self, pos: Coord<I>) -> &mut Self::Output {
+        let o = self.offset(pos.x, pos.y);
+        &mut self.pixels[o]
+    }
+}
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 {
+                0xedb88320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+}
+
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+fn crc32(parts: &[&[u8]]) -> u32 {
+    let mut c = u32::MAX;
+    for &b in parts.iter().flat_map(|p| p.iter()) {
+        c = CRC32_TABLE[((c ^ b as u32) & 0xff) as usize] ^ (c >> 8);
+    }
+    !c
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1_u32, 0_u32);
+    for chunk in data.chunks(5552) {
+        for &d in chunk {
+            a += d as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    b << 16 | a
+}
+
+// PNG requires a zlib stream, but not a compressed one. Stored blocks keep this dependency free.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() + data.len() / 65535 * 5 + 11);
+    out.extend_from_slice(&[0x78, 0x01]);
+    let mut chunks = data.chunks(65535).peekable();
+    if chunks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(chunk) = chunks.next() {
+        let len = chunk.len() as u16;
+        out.push(chunks.peek().is_none() as u8);
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(chunk);
+    }
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn write_png_chunk(w: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    w.write_all(&(data.len() as u32).to_be_bytes())?;
+    w.write_all(kind)?;
+    w.write_all(data)?;
+    w.write_all(&crc32(&[kind, data]).to_be_bytes())
+}
+
+struct GifWriter<W: Write> {
+    w: W,
+    width: u16,
+    height: u16,
+    delay: u16,
+}
+
+impl<W: Write> GifWriter<W> {
+    fn new(mut w: W, width: usize, height: usize, delay: u16) -> io::Result<Self> {
+        let (Ok(width), Ok(height)) = (u16::try_from(width), u16::try_from(height)) else {
+            return Err(io::Error::other("Image too large for GIF"));
+        };
+        w.write_all(b"GIF89a")?;
+        w.write_all(&width.to_le_bytes())?;
+        w.write_all(&height.to_le_bytes())?;
+        w.write_all(&[0, 0, 0])?;
+        // Loop forever
+        w.write_all(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00")?;
+        Ok(Self {
+            w,
+            width,
+            height,
+            delay,
+        })
+    }
+
+    fn frame(&mut self, img: &Image) -> io::Result<()> {
+        if img.width != self.width as usize || img.height != self.height as usize {
+            return Err(io::Error::other("GIF frames must all have the same size"));
+        }
+
+        let mut colors = HashMap::new();
+        let mut palette = Vec::new();
+        let mut indices = Vec::with_capacity(img.pixels.len());
+        for &p in &img.pixels {
+            let idx = *colors.entry(p).or_insert_with(|| {
+                palette.push(p);
+                palette.len() - 1
+            });
+            if idx > 255 {
+                return Err(io::Error::other("GIF frames can have at most 256 colors"));
+            }
+            indices.push(idx as u8);
+        }
+
+        let bits = palette.len().next_power_of_two().trailing_zeros().max(1);
+        palette.resize(1 << bits, BLACK);
+
+        self.w.write_all(&[0x21, 0xf9, 4, 0])?;
+        self.w.write_all(&self.delay.to_le_bytes())?;
+        self.w.write_all(&[0, 0])?;
+
+        self.w.write_all(&[0x2c, 0, 0, 0, 0])?;
+        self.w.write_all(&self.width.to_le_bytes())?;
+        self.w.write_all(&self.height.to_le_bytes())?;
+        self.w.write_all(&[0x80 | (bits - 1) as u8])?;
+        self.w.write_all(palette.as_flattened())?;
+
+        let min_code_size = bits.max(2) as u8;
+        self.w.write_all(&[min_code_size])?;
+        for block in lzw_encode(&indices, min_code_size).chunks(255) {
+            self.w.write_all(&[block.len() as u8])?;
+            self.w.write_all(block)?;
+        }
+        self.w.write_all(&[0])
+    }
+
+    fn finish(mut self) -> io::Result<()> {
+        self.w.write_all(&[0x3b])?;
+        self.w.flush()
+    }
+}
+
+fn lzw_encode(data: &[u8], min_code_size: u8) -> Vec<u8> {
+    const MAX_CODE: u16 = 4095;
+
+    let mut out = Vec::with_capacity(data.len() / 2);
+    let (mut acc, mut acc_bits) = (0_u32, 0);
+    let mut emit = |code: u16, width: u32| {
+        acc |= (code as u32) << acc_bits;
+        acc_bits += width;
+        while acc_bits >= 8 {
+            out.push(acc as u8);
+            acc >>= 8;
+            acc_bits -= 8;
+        }
+    };
+
+    let clear = 1_u16 << min_code_size;
+    let eoi = clear + 1;
+    let mut dict = HashMap::<(u16, u8), u16>::new();
+    let mut next = eoi + 1;
+    let mut width = min_code_size as u32 + 1;
+
+    emit(clear, width);
+    let Some((&first, rest)) = data.split_first() else {
+        emit(eoi, width);
+        emit(0, 7);
+        return out;
+    };
+
+    let mut prefix = first as u16;
+    for &k in rest {
+        if let Some(&code) = dict.get(&(prefix, k)) {
+            prefix = code;
+            continue;
+        }
+
+        emit(prefix, width);
+        if next <= MAX_CODE {
+            dict.insert((prefix, k), next);
+            if next == 1 << width && width < 12 {
+                width += 1;
+            }
+            next += 1;
+        } else {
+            emit(clear, width);
+            dict.clear();
+            next = eoi + 1;
+            width = min_code_size as u32 + 1;
+        }
+        prefix = k as u16;
+    }
+    emit(prefix, width);
+    emit(eoi, width);
+    emit(0, 7);
+    out
+}
+
+fn with_path(e: io::Error, path: &Path) -> io::Error {
+    io::Error::new(e.kind(), format!("Can't record to {}: {e}", path.display()))
+}
+
+enum RecorderKind {
+    Gif(Option<GifWriter<BufWriter<File>>>, PathBuf, u16),
+    Directory(PathBuf),
+}
+
+pub struct FrameRecorder {
+    kind: RecorderKind,
+    scale: usize,
+    frames: usize,
+}
+
+impl FrameRecorder {
+    pub fn gif(path: impl AsRef<Path>, delay_cs: u16) -> Self {
+        Self {
+            kind: RecorderKind::Gif(None, path.as_ref().to_owned(), delay_cs),
+            scale: 1,
+            frames: 0,
+        }
+    }
+
+    pub fn directory(path: impl AsRef<Path>) -> io::Result<Self> {
+        std::fs::create_dir_all(path.as_ref()).map_err(|e| with_path(e, path.as_ref()))?;
+        Ok(Self {
+            kind: RecorderKind::Directory(path.as_ref().to_owned()),
+            scale: 1,
+            frames: 0,
+        })
+    }
+
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        if path.extension().is_some_and(|e| e == "gif") {
+            Ok(Self::gif(path, 10))
+        } else {
+            Self::directory(path)
+        }
+    }
+
+    pub fn with_scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    pub fn record(&mut self, img: &Image) -> io::Result<()> {
+        let scaled;
+        let img = if self.scale > 1 {
+            scaled = img.scaled(self.scale);
+            &scaled
+        } else {
+            img
+        };
+
+        match &mut self.kind {
+            RecorderKind::Gif(writer, path, delay) => {
+                if writer.is_none() {
+                    let file =
+                        BufWriter::new(File::create(&*path).map_err(|e| with_path(e, path))?);
+                    *writer = Some(GifWriter::new(file, img.width, img.height, *delay)?);
+                }
+                writer.as_mut().unwrap().frame(img)?;
+            }
+            RecorderKind::Directory(path) => {
+                img.save(path.join(format!("frame_{:05}.png", self.frames)))?;
+            }
+        }
+        self.frames += 1;
+        Ok(())
+    }
+
+    pub fn finish(self) -> io::Result<()> {
+        match self.kind {
+            RecorderKind::Gif(Some(writer), ..) => writer.finish(),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A plain GIF LZW decoder, written independently of the encoder
+    fn lzw_decode(data: &[u8], min_code_size: u8) -> Vec<u8> {
+        let clear = 1_usize << min_code_size;
+        let eoi = clear + 1;
+        let reset = |dict: &mut Vec<Vec<u8>>| {
+            dict.clear();
+            dict.extend((0..clear).map(|i| vec![i as u8]));
+            dict.extend([Vec::new(), Vec::new()]);
+        };
+
+        let mut dict = Vec::new();
+        reset(&mut dict);
+        let mut width = min_code_size as usize + 1;
+        let mut prev: Option<Vec<u8>> = None;
+        let mut out = Vec::new();
+        let mut bit = 0;
+        loop {
+            let mut code = 0;
+            for i in 0..width {
+                let b = data[(bit + i) / 8] >> ((bit + i) % 8) & 1;
+                code |= (b as usize) << i;
+            }
+            bit += width;
+
+            if code == clear {
+                reset(&mut dict);
+                width = min_code_size as usize + 1;
+                prev = None;
+                continue;
+            }
+            if code == eoi {
+                return out;
+            }
+
+            let entry = match (dict.get(code), &prev) {
+                (Some(e), _) => e.clone(),
+                (None, Some(p)) => {
+                    assert_eq!(code, dict.len(), "Code out of sequence");
+                    let mut e = p.clone();
+                    e.push(p[0]);
+                    e
+                }
+                (None, None) => panic!("Unknown code {code} after clear"),
+            };
+            out.extend_from_slice(&entry);
+            if let Some(mut p) = prev.take() {
+                if dict.len() < 4096 {
+                    p.push(entry[0]);
+                    dict.push(p);
+                }
+            }
+            if dict.len() == 1 << width && width < 12 {
+                width += 1;
+            }
+            prev = Some(entry);
+        }
+    }
+
+    #[test]
+    fn crc32_known_vectors() {
+        assert_eq!(crc32(&[]), 0);
+        assert_eq!(crc32(&[b"123456789"]), 0xcbf4_3926);
+        assert_eq!(crc32(&[b"1234", b"", b"56789"]), 0xcbf4_3926);
+        // The fixed CRC of every PNG's IEND chunk
+        assert_eq!(crc32(&[b"IEND"]), 0xae42_6082);
+    }
+
+    #[test]
+    fn adler32_known_vectors() {
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"a"), 0x0062_0062);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+        // Long enough to need the deferred modulo (value from zlib.adler32)
+        assert_eq!(adler32(&[0xff; 10000]), 0xb623_eb2b);
+    }
+
+    #[test]
+    fn zlib_stored_blocks() {
+        for len in [0, 1, 65535, 65536, 200_000] {
+            let data = (0..len).map(|i| (i * 7 % 251) as u8).collect::<Vec<_>>();
+            let z = zlib_stored(&data);
+            assert_eq!(&z[..2], &[0x78, 0x01]);
+            assert_eq!((z[0] as u16 * 256 + z[1] as u16) % 31, 0);
+
+            let mut pos = 2;
+            let mut out = Vec::new();
+            loop {
+                let last = z[pos] == 1;
+                let n = u16::from_le_bytes([z[pos + 1], z[pos + 2]]);
+                assert_eq!(!n, u16::from_le_bytes([z[pos + 3], z[pos + 4]]));
+                out.extend_from_slice(&z[pos + 5..pos + 5 + n as usize]);
+                pos += 5 + n as usize;
+                if last {
+                    break;
+                }
+            }
+            assert_eq!(out, data);
+            assert_eq!(z[pos..], adler32(&data).to_be_bytes());
+        }
+    }
+
+    #[test]
+    fn png_chunks() {
+        let mut img = Image::new(3, 2, BLACK);
+        img[crate::coord(1, 0)] = WHITE;
+        img[crate::coord(2, 1)] = [1, 2, 3];
+        let mut png = Vec::new();
+        img.write_png(&mut png).unwrap();
+
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        let mut pos = 8;
+        let mut kinds = Vec::new();
+        while pos < png.len() {
+            let len = u32::from_be_bytes(png[pos..pos + 4].try_into().unwrap()) as usize;
+            let kind = &png[pos + 4..pos + 8];
+            let data = &png[pos + 8..pos + 8 + len];
+            let crc = u32::from_be_bytes(png[pos + 8 + len..pos + 12 + len].try_into().unwrap());
+            assert_eq!(crc, crc32(&[kind, data]));
+            if kind == b"IDAT" {
+                let raw = [
+                    [0, 0, 0, 0, 255, 255, 255, 0, 0, 0],
+                    [0, 0, 0, 0, 0, 0, 0, 1, 2, 3],
+                ];
+                assert_eq!(data, zlib_stored(raw.as_flattened()));
+            }
+            kinds.push(kind.to_vec());
+            pos += 12 + len;
+        }
+        assert_eq!(kinds, [b"IHDR", b"IDAT", b"IEND"]);
+    }
+
+    #[test]
+    fn lzw_round_trip() {
+        let mut seed = 1_u32;
+        let mut random = |n: u32| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (seed >> 16) % n
+        };
+        let mut inputs: Vec<(Vec<u8>, u8)> = vec![
+            (vec![], 2),
+            (vec![0], 2),
+            (vec![1, 1, 1, 1, 1, 1, 1], 2),
+            (b"\x00\x01\x00\x01\x00\x01\x00\x01\x00\x02".to_vec(), 2),
+        ];
+        // Long enough to fill the dictionary and force clear codes
+        for bits in [2, 4, 8] {
+            let data = (0..100_000).map(|_| random(1 << bits) as u8).collect();
+            inputs.push((data, bits));
+        }
+        for (data, bits) in inputs {
+            assert_eq!(lzw_decode(&lzw_encode(&data, bits), bits), data);
+        }
+    }
+
+    #[test]
+    fn gif_frame_round_trip() {
+        let colors = [BLACK, WHITE, [255, 0, 0], [0, 0, 255], [0, 255, 0]];
+        let mut img = Image::new(7, 5, BLACK);
+        let mut expected = Vec::new();
+        for (i, p) in img.pixels.iter_mut().enumerate() {
+            *p = colors[i * i % 5];
+        }
+        // The encoder numbers colors in order of first appearance
+        let mut palette: Vec<Rgb> = Vec::new();
+        for p in &img.pixels {
+            let idx = palette.iter().position(|c| c == p).unwrap_or_else(|| {
+                palette.push(*p);
+                palette.len() - 1
+            });
+            expected.push(idx as u8);
+        }
+
+        let mut gif = Vec::new();
+        let mut w = GifWriter::new(&mut gif, 7, 5, 10).unwrap();
+        w.frame(&img).unwrap();
+        w.finish().unwrap();
+
+        // Header, screen descriptor, loop extension, graphic control and image descriptor
+        let packed = gif[49];
+        assert_eq!(packed & 0x80, 0x80);
+        let table_len = 3 << ((packed & 7) + 1);
+        let table = &gif[50..50 + table_len];
+        for (i, c) in palette.iter().enumerate() {
+            assert_eq!(&table[i * 3..i * 3 + 3], c);
+        }
+
+        let mut pos = 50 + table_len;
+        let min_code_size = gif[pos];
+        pos += 1;
+        let mut lzw = Vec::new();
+        while gif[pos] != 0 {
+            let n = gif[pos] as usize;
+            lzw.extend_from_slice(&gif[pos + 1..pos + 1 + n]);
+            pos += 1 + n;
+        }
+        assert_eq!(&gif[pos + 1..], &[0x3b]);
+        assert_eq!(lzw_decode(&lzw, min_code_size), expected);
+    }
+}
@@ -6,14 +6,14 @@ use std::{
     path::{Path, PathBuf},
 };
 
-pub struct Input(Mmap, std::time::Instant, bool);
+pub struct Input(Mmap, std::time::Instant, InputArgs);
 
 impl Input {
-    pub fn lines(&self) -> Lines {
+    pub fn lines(&self) -> Lines<'_> {
         Lines(self.bytes())
     }
 
-    pub fn paragraphs(&self) -> Paragraphs {
+    pub fn paragraphs(&self) -> Paragraphs<'_> {
         Paragraphs(self.bytes())
     }
 
@@ -38,7 +38,18 @@ impl Input {
     }
 
     pub fn is_example(&self) -> bool {
-        self.2
+        self.2.is_example
+    }
+
+    pub fn visualizer(&self) -> Visualizer {
+        Visualizer::new(self.2.visualize)
+    }
+
+    pub fn recorder(&self) -> std::io::Result<Option<image::FrameRecorder>> {
+        Ok(match &self.2.record {
+            Some(path) => Some(image::FrameRecorder::create(path)?),
+            None => None,
+        })
     }
 }
 
@@ -58,7 +69,7 @@ impl Drop for Input {
     fn drop(&mut self) {
         let n = std::time::Instant::now();
         let d = n - self.1;
-        if self.2 {
+        if self.2.is_example {
             println!("\x1b[91mEXAMPLE INPUT\x1b[0m");
         }
         println!("Time spent: {:.1}µs", d.as_nanos() as f32 / 1000.0);
@@ -67,6 +78,12 @@ impl Drop for Input {
 
 pub struct Lines<'a>(&'a [u8]);
 
+impl<'a> Lines<'a> {
+    pub fn new(b: &'a [u8]) -> Self {
+        Self(b)
+    }
+}
+
 impl<'a> Iterator for Lines<'a> {
     type Item = &'a str;
 
@@ -127,26 +144,57 @@ impl<'a> Iterator for Paragraphs<'a> {
     }
 }
 
-fn get_input_path(day: &str) -> Result<(PathBuf, bool)> {
-    let args = std::env::args().collect::<Vec<_>>();
-    if args.len() < 2 {
-        Ok((Path::new(day).join("data/input.txt"), false))
-    } else if args.len() == 2 && args[1].starts_with("-e") {
-        Ok((
-            Path::new(day).join(format!("data/example{}.txt", &args[1][2..])),
-            true,
-        ))
-    } else if args.len() == 3 && args[1] == "-i" {
-        Ok((PathBuf::from(&args[2]), false))
-    } else {
-        bail!("Bad command line arguments. Expected nothing, `-e`, or `-i <file>`")
+struct InputArgs {
+    path: PathBuf,
+    is_example: bool,
+    visualize: bool,
+    record: Option<PathBuf>,
+}
+
+fn get_input_args(day: &str) -> Result<InputArgs> {
+    let mut args = InputArgs {
+        path: Path::new(day).join("data/input.txt"),
+        is_example: false,
+        visualize: false,
+        record: None,
+    };
+    let mut path_set = false;
+
+    let mut it = std::env::args().skip(1);
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "-v" => args.visualize = true,
+            "-r" => {
+                let Some(path) = it.next() else {
+                    bail!("Missing path after `-r`");
+                };
+                args.record = Some(PathBuf::from(path));
+            }
+            "-i" if !path_set => {
+                let Some(path) = it.next() else {
+                    bail!("Missing path after `-i`");
+                };
+                args.path = PathBuf::from(path);
+                path_set = true;
+            }
+            e if e.starts_with("-e") && !path_set => {
+                args.path = Path::new(day).join(format!("data/example{}.txt", &e[2..]));
+                args.is_example = true;
+                path_set = true;
+            }
+            _ => bail!(
+                "Bad command line arguments. Expected nothing, `-e`, or `-i <file>`, optionally with `-v` and/or `-r <path>`"
+            ),
+        }
     }
+
+    Ok(args)
 }
 
 pub fn open_input(day: &str) -> Result<Input> {
     let t = std::time::Instant::now();
-    let (path, is_example) = get_input_path(day)?;
-    let file = File::open(path)?;
+    let args = get_input_args(day)?;
+    let file = File::open(&args.path)?;
     let mmap = unsafe { Mmap::map(&file)? };
 
     #[cfg(feature = "validation")]
@@ -154,5 +202,5 @@ pub fn open_input(day: &str) -> Result<Input> {
         bail!("Input contains non-ascii data");
     }
 
-    Ok(Input(mmap, t, is_example))
+    Ok(Input(mmap, t, args))
 }
@@ -1,19 +1,85 @@
 mod parser;
 pub use parser::*;
 
+mod hash;
+pub use hash::*;
+
 mod input;
 pub use input::*;
 
 mod primitives;
 pub use primitives::*;
 
+mod bigint;
+pub use bigint::*;
+
 mod field;
 pub use field::*;
 
+mod bitfield;
+pub use bitfield::*;
+
+mod padded;
+pub use padded::*;
+
+mod sparse;
+pub use sparse::*;
+
 mod coord;
 pub use coord::*;
 
-pub use util_macros::aoc_day;
+mod rect;
+pub use rect::*;
+
+mod dir;
+pub use dir::*;
+
+mod coord3;
+pub use coord3::*;
+
+mod hex;
+pub use hex::*;
+
+mod volume;
+pub use volume::*;
+
+mod render;
+pub use render::*;
+
+mod pattern;
+pub use pattern::*;
+
+mod disjoint;
+pub use disjoint::*;
+
+mod trie;
+pub use trie::*;
+
+mod queue;
+pub use queue::*;
+
+#[cfg(feature = "parallel")]
+mod parallel;
+#[cfg(feature = "parallel")]
+pub use parallel::*;
+
+pub mod image;
+
+pub mod cycle;
+
+pub mod geom;
+
+pub mod num;
+
+pub mod linalg;
+
+pub mod checked;
+
+pub mod memo;
+
+pub mod graph;
+
+pub use util_macros::{aoc_day, Cell};
 
 #[macro_export]
 macro_rules! current_day {
@@ -86,26 +152,6 @@ where
     a
 }
 
-mod helper {
-    pub trait DivEuclid {
-        fn div_euclid(self, other: Self) -> Self;
-    }
-
-    macro_rules! impl_div_euclid {
-        ($($t:ty),+) => {
-            $(
-                impl DivEuclid for $t {
-                    fn div_euclid(self, other: Self) -> Self {
-                        self.div_euclid(other)
-                    }
-                }
-            )+
-        };
-    }
-
-    impl_div_euclid!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize, f32, f64);
-}
-
 pub fn extended_euclidian<T>(mut a: T, mut b: T) -> (T, T, T)
 where
     T: Copy
@@ -114,7 +160,7 @@ where
         + std::ops::Sub<T, Output = T>
         + std::ops::Mul<T, Output = T>
         + Increment
-        + helper::DivEuclid,
+        + Euclid,
 {
     let zero = <T as Default>::default();
     let one = zero.get_inc();
@@ -0,0 +1,396 @@
+use crate::num::ext_gcd;
+use crate::{gcd, Int};
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+// An exact fraction, always stored in lowest terms with a positive denominator
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+impl Rational {
+    pub const ZERO: Self = Self { num: 0, den: 1 };
+    pub const ONE: Self = Self { num: 1, den: 1 };
+
+    pub fn new(num: i128, den: i128) -> Self {
+        assert!(den != 0, "Rational with zero denominator");
+        let g = gcd(num.abs(), den.abs()) * den.signum();
+        Self {
+            num: num / g,
+            den: den / g,
+        }
+    }
+
+    pub fn numer(&self) -> i128 {
+        self.num
+    }
+
+    pub fn denom(&self) -> i128 {
+        self.den
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == 1
+    }
+
+    pub fn to_integer(&self) -> Option<i128> {
+        self.is_integer().then_some(self.num)
+    }
+}
+
+impl<T: Int> From<T> for Rational {
+    fn from(v: T) -> Self {
+        Self {
+            num: v.to(),
+            den: 1,
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.num * rhs.den + rhs.num * self.den, self.den * rhs.den)
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(self.num * rhs.num, self.den * rhs.den)
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        Self::new(self.num * rhs.den, self.den * rhs.num)
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.num * other.den).cmp(&(other.num * self.den))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Solution {
+    None,
+    Unique(Vec<Rational>),
+    // Every solution is `particular` plus any linear combination of the `basis` vectors
+    Infinite {
+        particular: Vec<Rational>,
+        basis: Vec<Vec<Rational>>,
+    },
+}
+
+impl Solution {
+    // The unique solution, if there is one and all of its values are integers. This doesn't look for
+    // integer points in an infinite solution set: that is a lattice problem (Hermite normal form)
+    // rather than elimination, and Infinite always gives None here even when such points exist.
+    pub fn integer(&self) -> Option<Vec<i128>> {
+        match self {
+            Solution::Unique(x) => x.iter().map(Rational::to_integer).collect(),
+            _ => None,
+        }
+    }
+}
+
+fn det2(m: [[i128; 2]; 2]) -> i128 {
+    m[0][0] * m[1][1] - m[0][1] * m[1][0]
+}
+
+fn det3(m: [[i128; 3]; 3]) -> i128 {
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
+
+fn wide<T: Int, const N: usize>(a: [[T; N]; N], b: [T; N]) -> ([[i128; N]; N], [i128; N]) {
+    (a.map(|row| row.map(|v| v.to())), b.map(|v| v.to()))
+}
+
+// Solves a * x = b with Cramer's rule, or returns None if the determinant is 0
+pub fn cramer2<T: Int>(a: [[T; 2]; 2], b: [T; 2]) -> Option<[Rational; 2]> {
+    let (a, b) = wide(a, b);
+    let det = det2(a);
+    if det == 0 {
+        return None;
+    }
+    Some(std::array::from_fn(|i| {
+        let mut m = a;
+        for (row, &v) in m.iter_mut().zip(&b) {
+            row[i] = v;
+        }
+        Rational::new(det2(m), det)
+    }))
+}
+
+pub fn cramer3<T: Int>(a: [[T; 3]; 3], b: [T; 3]) -> Option<[Rational; 3]> {
+    let (a, b) = wide(a, b);
+    let det = det3(a);
+    if det == 0 {
+        return None;
+    }
+    Some(std::array::from_fn(|i| {
+        let mut m = a;
+        for (row, &v) in m.iter_mut().zip(&b) {
+            row[i] = v;
+        }
+        Rational::new(det3(m), det)
+    }))
+}
+
+// Solves a * x = b for any number of equations and unknowns. The elimination is fraction-free
+// (Bareiss), so all intermediate values stay integers and every division is exact. Only the back
+// substitution works on rationals.
+pub fn solve<T: Int>(a: &[Vec<T>], b: &[T]) -> Solution {
+    assert_eq!(a.len(), b.len(), "Row count mismatch");
+    let cols = a.first().map_or(0, Vec::len);
+    let mut m: Vec<Vec<i128>> = a
+        .iter()
+        .zip(b)
+        .map(|(row, &v)| {
+            assert_eq!(row.len(), cols, "Ragged matrix");
+            row.iter().chain([&v]).map(|&v| v.to()).collect()
+        })
+        .collect();
+
+    let mut pivots = Vec::with_capacity(cols);
+    let mut prev = 1;
+    for c in 0..cols {
+        let r = pivots.len();
+        let Some(p) = (r..m.len()).find(|&i| m[i][c] != 0) else {
+            continue;
+        };
+        m.swap(r, p);
+        for i in r + 1..m.len() {
+            for j in c + 1..=cols {
+                m[i][j] = (m[r][c] * m[i][j] - m[i][c] * m[r][j]) / prev;
+            }
+            m[i][c] = 0;
+        }
+        prev = m[r][c];
+        pivots.push(c);
+    }
+
+    if m[pivots.len()..].iter().any(|row| row[cols] != 0) {
+        return Solution::None;
+    }
+
+    // Fills in the pivot variables given the free ones, with either the real right hand side or zeros
+    let back_substitute = |mut x: Vec<Rational>, homogeneous: bool| {
+        for (r, &c) in pivots.iter().enumerate().rev() {
+            let rhs = if homogeneous { 0 } else { m[r][cols] };
+            let sum = (c + 1..cols).fold(Rational::from(rhs), |acc, j| {
+                acc - Rational::from(m[r][j]) * x[j]
+            });
+            x[c] = sum / Rational::from(m[r][c]);
+        }
+        x
+    };
+
+    let particular = back_substitute(vec![Rational::ZERO; cols], false);
+    if pivots.len() == cols {
+        return Solution::Unique(particular);
+    }
+    let basis = (0..cols)
+        .filter(|c| !pivots.contains(c))
+        .map(|free| {
+            let mut x = vec![Rational::ZERO; cols];
+            x[free] = Rational::ONE;
+            back_substitute(x, true)
+        })
+        .collect();
+    Solution::Infinite { particular, basis }
+}
+
+// Finds the non-negative integer solution of a * [x, y] = b with the lowest cost[0] * x + cost[1] * y.
+// Unlike cramer2 this also handles a singular matrix, where the two equations describe the same line
+// and there can be many solutions to choose from. Returns None if there is no such solution, if the
+// cost has no lower bound, or if the answer doesn't fit into T.
+//
+// This is deliberately limited to two unknowns, where the solutions of a singular system lie on a
+// single line that can be searched in closed form. With more unknowns it becomes integer linear
+// programming; use solve and check the result instead.
+pub fn solve2_nonneg_integer<T: Int>(a: [[T; 2]; 2], b: [T; 2], cost: [T; 2]) -> Option<[T; 2]> {
+    let (a, b) = wide(a, b);
+    let cost = cost.map(|v| -> i128 { v.to() });
+    let narrow = |[x, y]: [i128; 2]| Some([T::try_from_primitive(x)?, T::try_from_primitive(y)?]);
+
+    let det = det2(a);
+    if det != 0 {
+        let x = det2([[b[0], a[0][1]], [b[1], a[1][1]]]);
+        let y = det2([[a[0][0], b[0]], [a[1][0], b[1]]]);
+        if x % det != 0 || y % det != 0 || x / det < 0 || y / det < 0 {
+            return None;
+        }
+        return narrow([x / det, y / det]);
+    }
+
+    // Both equations are multiples of the same p * x + q * y = r, or they contradict each other
+    let Some(k) = (0..2).find(|&i| a[i] != [0, 0]) else {
+        return if b == [0, 0] && cost.iter().all(|&c| c >= 0) {
+            narrow([0, 0])
+        } else {
+            None
+        };
+    };
+    let ([p, q], r) = (a[k], b[k]);
+    let ([p2, q2], r2) = (a[1 - k], b[1 - k]);
+    if p2 * r != p * r2 || q2 * r != q * r2 {
+        return None;
+    }
+
+    // All integer solutions are (x0, y0) + t * (dx, dy)
+    let (g, s, u) = ext_gcd(p.abs(), q.abs());
+    if r % g != 0 {
+        return None;
+    }
+    let (x0, y0) = (s * p.signum() * (r / g), u * q.signum() * (r / g));
+    let (dx, dy) = (q / g, -p / g);
+
+    // Range of t that keeps both x and y non-negative, where None means unbounded
+    let (mut lo, mut hi) = (None, None);
+    for (v0, d) in [(x0, dx), (y0, dy)] {
+        match d.cmp(&0) {
+            Ordering::Greater => lo = lo.max(Some(-v0.div_euclid(d))),
+            Ordering::Less => {
+                hi = Some(hi.map_or(v0.div_euclid(-d), |h: i128| h.min(v0.div_euclid(-d))))
+            }
+            Ordering::Equal if v0 < 0 => return None,
+            Ordering::Equal => {}
+        }
+    }
+
+    let t = match (cost[0] * dx + cost[1] * dy).cmp(&0) {
+        Ordering::Greater => lo?,
+        Ordering::Less => hi?,
+        Ordering::Equal => lo.or(hi).unwrap_or(0),
+    };
+    if lo.is_some_and(|lo| t < lo) || hi.is_some_and(|hi| t > hi) {
+        return None;
+    }
+    narrow([x0 + t * dx, y0 + t * dy])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn r(num: i128, den: i128) -> Rational {
+        Rational::new(num, den)
+    }
+
+    fn ints(v: &[i128]) -> Vec<Rational> {
+        v.iter().map(|&v| Rational::from(v)).collect()
+    }
+
+    #[test]
+    fn cramer() {
+        assert_eq!(cramer2([[1, 1], [1, -1]], [3, 1]), Some([r(2, 1), r(1, 1)]));
+        assert_eq!(cramer2([[2, 0], [0, 4]], [1, 2]), Some([r(1, 2), r(1, 2)]));
+        assert_eq!(cramer2([[1, 2], [2, 4]], [3, 6]), None);
+        assert_eq!(
+            cramer3([[2, 0, 0], [0, 3, 0], [0, 0, 4]], [1, 3, 8]),
+            Some([r(1, 2), r(1, 1), r(2, 1)])
+        );
+        assert_eq!(cramer3([[1, 2, 3], [4, 5, 6], [7, 8, 9]], [1, 2, 3]), None);
+    }
+
+    #[test]
+    fn elimination() {
+        let unique = solve(&[vec![1, 1], vec![1, -1]], &[3, 1]);
+        assert_eq!(unique, Solution::Unique(ints(&[2, 1])));
+        assert_eq!(unique.integer(), Some(vec![2, 1]));
+
+        let fractional = solve(&[vec![2, 0], vec![0, 4]], &[1, 2]);
+        assert_eq!(fractional, Solution::Unique(vec![r(1, 2), r(1, 2)]));
+        assert_eq!(fractional.integer(), None);
+
+        // Singular, and either consistent or not
+        let line = solve(&[vec![1, 2], vec![2, 4]], &[3, 6]);
+        assert_eq!(
+            line,
+            Solution::Infinite {
+                particular: ints(&[3, 0]),
+                basis: vec![ints(&[-2, 1])]
+            }
+        );
+        assert_eq!(line.integer(), None);
+        assert_eq!(solve(&[vec![1, 2], vec![2, 4]], &[3, 7]), Solution::None);
+
+        // More equations than unknowns
+        let a = [vec![1, 0], vec![0, 1], vec![1, 1]];
+        assert_eq!(solve(&a, &[2, 3, 5]), Solution::Unique(ints(&[2, 3])));
+        assert_eq!(solve(&a, &[2, 3, 6]), Solution::None);
+    }
+
+    #[test]
+    fn nonneg_integer() {
+        let solve2 = solve2_nonneg_integer::<i64>;
+        assert_eq!(
+            solve2([[94, 22], [34, 67]], [8400, 5400], [3, 1]),
+            Some([80, 40])
+        );
+        // Not integer, negative
+        assert_eq!(solve2([[2, 0], [0, 2]], [1, 2], [1, 1]), None);
+        assert_eq!(solve2([[1, 0], [0, 1]], [-1, 2], [1, 1]), None);
+
+        // Singular: the cheapest point on the line, no integer points, contradicting equations
+        assert_eq!(solve2([[1, 1], [2, 2]], [5, 10], [3, 1]), Some([0, 5]));
+        assert_eq!(solve2([[1, 1], [2, 2]], [5, 10], [1, 3]), Some([5, 0]));
+        assert_eq!(solve2([[2, 4], [4, 8]], [7, 14], [1, 1]), None);
+        assert_eq!(solve2([[2, 4], [1, 2]], [6, 4], [1, 1]), None);
+        // x = y has no cheapest point when the cost falls along it
+        assert_eq!(solve2([[1, -1], [2, -2]], [0, 0], [-1, 0]), None);
+        assert_eq!(solve2([[0, 0], [0, 0]], [0, 0], [1, 1]), Some([0, 0]));
+        assert_eq!(solve2([[0, 0], [0, 0]], [0, 0], [-1, 1]), None);
+        assert_eq!(solve2([[0, 0], [0, 0]], [1, 0], [1, 1]), None);
+
+        // y = 100 and x - y = 100 has x = 200, which doesn't fit into an i8
+        let solve2_i8 = solve2_nonneg_integer::<i8>;
+        assert_eq!(
+            solve2_i8([[1, -1], [0, 1]], [27, 100], [1, 1]),
+            Some([127, 100])
+        );
+        assert_eq!(solve2_i8([[1, -1], [0, 1]], [100, 100], [1, 1]), None);
+    }
+}
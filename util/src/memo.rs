@@ -0,0 +1,336 @@
+use crate::{FastHashMap, ToPrimitive};
+use std::{collections::BTreeMap, fmt, hash::Hash, marker::PhantomData};
+
+// Where a Memo keeps its values. `get` takes &mut self so a backend can track usage.
+pub trait Backend<K, V> {
+    fn get(&mut self, key: &K) -> Option<&V>;
+    fn insert(&mut self, key: K, value: V);
+    fn len(&self) -> usize;
+    fn clear(&mut self);
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+pub struct HashBackend<K, V>(FastHashMap<K, V>);
+
+impl<K: Hash + Eq, V> Backend<K, V> for HashBackend<K, V> {
+    fn get(&mut self, key: &K) -> Option<&V> {
+        self.0.get(key)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        self.0.insert(key, value);
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+// For small integer keys, a plain array indexed by the key
+pub struct DenseBackend<V> {
+    values: Vec<Option<V>>,
+    len: usize,
+}
+
+impl<K: Copy + ToPrimitive<usize>, V> Backend<K, V> for DenseBackend<V> {
+    fn get(&mut self, key: &K) -> Option<&V> {
+        self.values[key.to()].as_ref()
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        let slot = &mut self.values[key.to()];
+        self.len += slot.is_none() as usize;
+        *slot = Some(value);
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn clear(&mut self) {
+        self.values.iter_mut().for_each(|v| *v = None);
+        self.len = 0;
+    }
+}
+
+// Keeps at most `capacity` values and evicts the least recently used one when full
+pub struct LruBackend<K, V> {
+    capacity: usize,
+    tick: u64,
+    values: FastHashMap<K, (V, u64)>,
+    order: BTreeMap<u64, K>,
+}
+
+impl<K: Hash + Eq + Clone, V> Backend<K, V> for LruBackend<K, V> {
+    fn get(&mut self, key: &K) -> Option<&V> {
+        let (value, used) = self.values.get_mut(key)?;
+        let k = self.order.remove(used).unwrap();
+        self.tick += 1;
+        *used = self.tick;
+        self.order.insert(self.tick, k);
+        Some(value)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        self.tick += 1;
+        if let Some((_, used)) = self.values.get(&key) {
+            self.order.remove(used);
+        } else if self.values.len() >= self.capacity {
+            if let Some((_, oldest)) = self.order.pop_first() {
+                self.values.remove(&oldest);
+            }
+        }
+        self.order.insert(self.tick, key.clone());
+        self.values.insert(key, (value, self.tick));
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn clear(&mut self) {
+        self.values.clear();
+        self.order.clear();
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Stats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.hits + self.misses;
+        let rate = if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 * 100.0 / total as f64
+        };
+        write!(
+            f,
+            "{} hits, {} misses ({rate:.1}% hit rate)",
+            self.hits, self.misses
+        )
+    }
+}
+
+// A cache for the results of a recursive function. Pass it down through the recursion and wrap the
+// body in `get_or_insert_with`, which hands the memo back to the closure for the recursive calls.
+pub struct Memo<K, V, B = HashBackend<K, V>> {
+    backend: B,
+    // Only counted in debug builds, to keep the release fast path free of bookkeeping
+    #[cfg(debug_assertions)]
+    stats: Stats,
+    _marker: PhantomData<(K, V)>,
+}
+
+impl<K: Hash + Eq, V> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::from_backend(HashBackend(FastHashMap::with_capacity_and_hasher(
+            capacity,
+            Default::default(),
+        )))
+    }
+}
+
+impl<K: Hash + Eq, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Copy + ToPrimitive<usize>, V> Memo<K, V, DenseBackend<V>> {
+    // Keys must be in 0..len
+    pub fn dense(len: usize) -> Self {
+        Self::from_backend(DenseBackend {
+            values: std::iter::repeat_with(|| None).take(len).collect(),
+            len: 0,
+        })
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Memo<K, V, LruBackend<K, V>> {
+    pub fn lru(capacity: usize) -> Self {
+        assert!(capacity > 0, "LRU memo needs room for at least one value");
+        Self::from_backend(LruBackend {
+            capacity,
+            tick: 0,
+            values: FastHashMap::with_capacity_and_hasher(capacity, Default::default()),
+            order: BTreeMap::new(),
+        })
+    }
+}
+
+impl<K, V, B: Backend<K, V>> Memo<K, V, B> {
+    pub fn from_backend(backend: B) -> Self {
+        Self {
+            backend,
+            #[cfg(debug_assertions)]
+            stats: Stats::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn get(&mut self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        let v = self.backend.get(key).cloned();
+        #[cfg(debug_assertions)]
+        match v {
+            Some(_) => self.stats.hits += 1,
+            None => self.stats.misses += 1,
+        }
+        v
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        self.backend.insert(key, value);
+    }
+
+    pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce(&mut Self) -> V) -> V
+    where
+        V: Clone,
+    {
+        if let Some(v) = self.get(&key) {
+            return v;
+        }
+        let v = f(self);
+        self.insert(key, v.clone());
+        v
+    }
+
+    pub fn len(&self) -> usize {
+        self.backend.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.backend.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.backend.clear();
+    }
+
+    // Always zero in release builds
+    pub fn stats(&self) -> Stats {
+        #[cfg(debug_assertions)]
+        return self.stats;
+        #[cfg(not(debug_assertions))]
+        Stats::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fib<B: Backend<u64, u64>>(n: u64, memo: &mut Memo<u64, u64, B>) -> u64 {
+        if n < 2 {
+            return n;
+        }
+        memo.get_or_insert_with(n, |memo| fib(n - 1, memo) + fib(n - 2, memo))
+    }
+
+    #[test]
+    fn recursion_under_each_backend() {
+        let mut hash = Memo::new();
+        assert_eq!(fib(90, &mut hash), 2_880_067_194_370_816_120);
+        assert_eq!(hash.len(), 89);
+
+        let mut dense = Memo::dense(91);
+        assert_eq!(fib(90, &mut dense), 2_880_067_194_370_816_120);
+        assert_eq!(dense.len(), 89);
+
+        // The recursion only ever looks back three values
+        let mut lru = Memo::lru(3);
+        assert_eq!(fib(90, &mut lru), 2_880_067_194_370_816_120);
+        assert_eq!(lru.len(), 3);
+
+        #[cfg(debug_assertions)]
+        assert_eq!(
+            hash.stats(),
+            Stats {
+                hits: 87,
+                misses: 89
+            }
+        );
+        hash.clear();
+        assert!(hash.is_empty());
+    }
+
+    #[test]
+    fn lru_evicts_the_least_recently_used() {
+        let mut memo = Memo::lru(3);
+        for k in 0..3 {
+            memo.insert(k, k * 10);
+        }
+        // Reading 0 makes 1 the oldest
+        assert_eq!(memo.get(&0), Some(0));
+        memo.insert(3, 30);
+        assert_eq!(memo.len(), 3);
+        assert_eq!(memo.get(&1), None);
+
+        // Overwriting refreshes without growing
+        memo.insert(2, 21);
+        assert_eq!(memo.len(), 3);
+        memo.insert(4, 40);
+        assert_eq!(memo.get(&0), None);
+        assert_eq!(memo.get(&2), Some(21));
+        assert_eq!(memo.get(&3), Some(30));
+        assert_eq!(memo.get(&4), Some(40));
+
+        memo.clear();
+        assert!(memo.is_empty());
+        memo.insert(5, 50);
+        assert_eq!(memo.get(&5), Some(50));
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one value")]
+    fn lru_needs_capacity() {
+        Memo::<u32, u32, _>::lru(0);
+    }
+
+    #[test]
+    fn dense_bounds() {
+        let mut memo = Memo::<usize, char, _>::dense(4);
+        assert_eq!(memo.get(&0), None);
+        memo.insert(3, 'c');
+        memo.insert(3, 'd');
+        assert_eq!(memo.len(), 1);
+        assert_eq!(memo.get(&3), Some('d'));
+        memo.clear();
+        assert_eq!((memo.len(), memo.get(&3)), (0, None));
+    }
+
+    #[test]
+    #[should_panic]
+    fn dense_key_out_of_range() {
+        let mut memo = Memo::<usize, u8, _>::dense(4);
+        memo.insert(4, 0);
+    }
+
+    #[test]
+    fn stats_display() {
+        let stats = Stats { hits: 3, misses: 1 };
+        assert_eq!(stats.to_string(), "3 hits, 1 misses (75.0% hit rate)");
+        assert_eq!(
+            Stats::default().to_string(),
+            "0 hits, 0 misses (0.0% hit rate)"
+        );
+    }
+}
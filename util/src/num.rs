@@ -0,0 +1,253 @@
+use crate::{gcd, Int};
+
+// Everything in here works on any primitive integer with values of up to 64 bits. Intermediate results
+// are computed in 128 bits so products of two such values can't overflow.
+
+fn wide<T: Int>(v: T) -> i128 {
+    v.to()
+}
+
+// Panics instead of wrapping when the result doesn't fit into T
+fn narrow<T: Int>(v: i128) -> T {
+    T::try_from_primitive(v).unwrap_or_else(|| panic!("{v} doesn't fit into the result type"))
+}
+
+// a * b mod m for a and b in 0..m. The product of two values of up to 64 bits can exceed i128, but
+// not u128.
+fn mul_mod_wide(a: i128, b: i128, m: i128) -> i128 {
+    (a as u128 * b as u128 % m as u128) as i128
+}
+
+pub fn lcm<T: Int>(a: T, b: T) -> T {
+    if a == T::default() || b == T::default() {
+        return T::default();
+    }
+    a / gcd(a, b) * b
+}
+
+// (a * b) mod m in the range 0..m, also for negative a or b. m must be positive.
+pub fn mul_mod<T: Int>(a: T, b: T, m: T) -> T {
+    let m = wide(m);
+    assert!(m > 0, "Modulus must be positive");
+    narrow(mul_mod_wide(
+        wide(a).rem_euclid(m),
+        wide(b).rem_euclid(m),
+        m,
+    ))
+}
+
+pub fn mod_pow<T: Int>(base: T, exp: u64, m: T) -> T {
+    let m = wide(m);
+    assert!(m > 0, "Modulus must be positive");
+    let mut base = wide(base).rem_euclid(m);
+    let mut exp = exp;
+    let mut r = 1 % m;
+    while exp > 0 {
+        if exp & 1 != 0 {
+            r = mul_mod_wide(r, base, m);
+        }
+        base = mul_mod_wide(base, base, m);
+        exp >>= 1;
+    }
+    narrow(r)
+}
+
+// Returns (g, x, y) with a*x + b*y = g = gcd(a, b)
+pub(crate) fn ext_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut r0, mut r1) = (a, b);
+    let (mut s0, mut s1) = (1, 0);
+    let (mut t0, mut t1) = (0, 1);
+    while r1 != 0 {
+        let q = r0.div_euclid(r1);
+        (r0, r1) = (r1, r0 - q * r1);
+        (s0, s1) = (s1, s0 - q * s1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+    (r0, s0, t0)
+}
+
+// The x in 0..m with a*x = 1 (mod m), if a and m are coprime. None for m = 0, which has no inverses.
+pub fn mod_inverse<T: Int>(a: T, m: T) -> Option<T> {
+    let m = wide(m);
+    if m == 0 {
+        return None;
+    }
+    let (g, x, _) = ext_gcd(wide(a).rem_euclid(m), m);
+    (g == 1).then(|| narrow(x.rem_euclid(m)))
+}
+
+// Solves x = r (mod m) for all (r, m) pairs at once. The moduli don't need to be coprime. Returns
+// the smallest non-negative solution along with the lcm of the moduli, or None if there is no solution
+// or a modulus is 0.
+pub fn crt<T: Int>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    let mut x = 0_i128;
+    let mut m = 1_i128;
+    for &(r2, m2) in congruences {
+        let (r2, m2) = (wide(r2), wide(m2));
+        if m2 == 0 {
+            return None;
+        }
+        let (g, p, _) = ext_gcd(m, m2);
+        let diff = r2 - x;
+        if diff % g != 0 {
+            return None;
+        }
+        let step = m2 / g;
+        let k = mul_mod_wide((diff / g).rem_euclid(step), p.rem_euclid(step), step);
+        x += m * k;
+        m = m
+            .checked_mul(step)
+            .expect("The lcm of the moduli overflows");
+        x = x.rem_euclid(m);
+    }
+    Some((narrow(x), narrow(m)))
+}
+
+// floor(sqrt(n)), n must not be negative
+pub fn isqrt<T: Int>(n: T) -> T {
+    let n = wide(n);
+    assert!(n >= 0, "isqrt of negative number");
+    narrow((n as u128).isqrt() as i128)
+}
+
+// Number of decimal digits, where 0 has a single digit. The sign is ignored.
+pub fn num_digits<T: Int>(n: T) -> u32 {
+    wide(n).unsigned_abs().checked_ilog10().unwrap_or(0) + 1
+}
+
+// The smallest power of 10 greater than n, so that a * next_pow10(b) + b concatenates a and b
+pub fn next_pow10<T: Int>(n: T) -> T {
+    narrow(10_i128.pow(num_digits(n)))
+}
+
+// Splits a non-negative number with an even number of digits into its left and right halves
+pub fn split_digits<T: Int>(n: T) -> Option<(T, T)> {
+    let digits = num_digits(n);
+    if !digits.is_multiple_of(2) {
+        return None;
+    }
+    let div = 10_i128.pow(digits / 2);
+    let n = wide(n);
+    Some((narrow(n / div), narrow(n % div)))
+}
+
+// The decimal digits of a non-negative number, most significant first
+pub fn digits<T: Int>(n: T) -> impl Iterator<Item = u8> {
+    let n = wide(n);
+    (0..num_digits(n))
+        .rev()
+        .map(move |i| (n / 10_i128.pow(i) % 10) as u8)
+}
+
+// n choose k, which is 0 when k > n. Panics if the result doesn't fit into T.
+pub fn binomial<T: Int>(n: T, k: T) -> T {
+    let (n, k) = (wide(n), wide(k));
+    if k < 0 || k > n {
+        return T::default();
+    }
+    let k = k.min(n - k);
+    let mut r = 1_i128;
+    for i in 0..k {
+        // r is n choose i here. Dividing out the common factor first keeps the product at exactly
+        // n choose i+1, so it only overflows when the result itself does.
+        let g = gcd(r, i + 1);
+        r = (r / g)
+            .checked_mul((n - i) / ((i + 1) / g))
+            .expect("Binomial coefficient overflows");
+    }
+    narrow(r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crt_cases() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // Moduli that share a factor, with and without a solution
+        assert_eq!(crt(&[(2, 6), (8, 10)]), Some((8, 30)));
+        assert_eq!(crt(&[(1, 6), (2, 4)]), None);
+        assert_eq!(crt(&[(-1, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(crt::<i64>(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(1, 3), (0, 0)]), None);
+        assert_eq!(
+            crt(&[(1_u64, 1 << 40), (2, (1 << 23) - 1)]),
+            Some((70_368_744_177_665, 9_223_370_937_343_148_032))
+        );
+    }
+
+    #[test]
+    fn inverse() {
+        assert_eq!(mod_inverse(3, 7), Some(5));
+        assert_eq!(mod_inverse(-3, 7), Some(2));
+        assert_eq!(mod_inverse(4, 8), None);
+        assert_eq!(mod_inverse(3, 0), None);
+        assert_eq!(mod_inverse(5, 1), Some(0));
+        let m = u64::MAX - 58; // The largest 64 bit prime
+        let inv = mod_inverse(u64::MAX, m).unwrap();
+        assert_eq!(mul_mod(inv, u64::MAX, m), 1);
+    }
+
+    #[test]
+    fn mod_arithmetic_near_max() {
+        let m = u64::MAX;
+        assert_eq!(mul_mod(m - 1, m - 1, m), 1);
+        assert_eq!(mul_mod(-1_i64, i64::MAX, i64::MAX), 0);
+        assert_eq!(mul_mod(i64::MIN, 3, 1_000_000_007), 126_483_995);
+        assert_eq!(mod_pow(m - 1, 1_000_001, m), m - 1);
+        assert_eq!(mod_pow(2_u64, 64, m), 1);
+        assert_eq!(mod_pow(7, 0, 1), 0);
+        assert_eq!(mod_pow(u8::MAX - 1, 3, u8::MAX), u8::MAX - 1);
+    }
+
+    #[test]
+    fn square_roots() {
+        for r in [1_u64, 2, 9, 1 << 20, u32::MAX as u64] {
+            let sq = r * r;
+            assert_eq!((isqrt(sq - 1), isqrt(sq), isqrt(sq + 1)), (r - 1, r, r));
+        }
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(u64::MAX), u32::MAX as u64);
+    }
+
+    #[test]
+    fn powers_of_ten() {
+        for p in 0..19 {
+            let n = 10_u64.pow(p);
+            assert_eq!((num_digits(n - 1), num_digits(n)), (p.max(1), p + 1));
+            assert_eq!((next_pow10(n - 1), next_pow10(n)), (n.max(10), n * 10));
+        }
+        assert_eq!(num_digits(0), 1);
+        assert_eq!(num_digits(-100), 3);
+        assert_eq!(split_digits(10), Some((1, 0)));
+        assert_eq!(split_digits(100), None);
+        assert_eq!(split_digits(1000), Some((10, 0)));
+        assert_eq!(split_digits(999_999_u32), Some((999, 999)));
+        assert_eq!(split_digits(0), None);
+        assert_eq!(digits(1000).collect::<Vec<_>>(), [1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn binomials() {
+        assert_eq!((binomial(5, 2), binomial(5, 0), binomial(5, 5)), (10, 1, 1));
+        assert_eq!((binomial(5, 6), binomial(5, -1)), (0, 0));
+        assert_eq!(binomial(67_u64, 33), 14_226_520_737_620_288_370);
+        assert_eq!(
+            binomial(130_i128, 65),
+            95_067_625_827_960_698_145_584_333_020_095_113_100
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't fit")]
+    fn binomial_too_big_for_type() {
+        binomial(40_u32, 20);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows")]
+    fn binomial_too_big_for_i128() {
+        binomial(200_i128, 100);
+    }
+}
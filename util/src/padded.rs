@@ -0,0 +1,201 @@
+use crate::{coord, Coord, Field, FieldView, FromPrimitive, Grid, PrimitiveInt, ToPrimitive};
+use std::ops::{Index, IndexMut};
+
+// A copy of a field surrounded by `pad` cells of border on every side, so that code stepping at most
+// `pad` cells outside of the original field can index it directly without any bounds checks.
+#[derive(Clone)]
+pub struct PaddedGrid<T> {
+    data: Vec<T>,
+    width: usize,
+    height: usize,
+    stride: usize,
+    pad: usize,
+}
+
+impl<T: Clone> PaddedGrid<T> {
+    pub fn new<F: Field<Item = T> + ?Sized>(field: &F, pad: usize, border: T) -> Self {
+        let (width, height) = (field.width(), field.height());
+        let stride = width + 2 * pad;
+        let mut data = vec![border; stride * (height + 2 * pad)];
+        for (y, row) in field.rows().enumerate() {
+            let o = (y + pad) * stride + pad;
+            data[o..o + width].clone_from_slice(row);
+        }
+        Self {
+            data,
+            width,
+            height,
+            stride,
+            pad,
+        }
+    }
+}
+
+impl<T> PaddedGrid<T> {
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    pub fn pad(&self) -> usize {
+        self.pad
+    }
+
+    pub fn data(&self) -> &[T] {
+        &self.data
+    }
+
+    pub fn data_mut(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+
+    pub fn offset<I: PrimitiveInt + ToPrimitive<isize>>(&self, x: I, y: I) -> usize {
+        let (x, y): (isize, isize) = (x.to(), y.to());
+        ((y + self.pad as isize) * self.stride as isize + x + self.pad as isize) as usize
+    }
+
+    pub fn offset_coord<I: PrimitiveInt + ToPrimitive<isize>>(&self, p: Coord<I>) -> usize {
+        self.offset(p.x, p.y)
+    }
+
+    pub fn delta<I: PrimitiveInt + ToPrimitive<isize>>(&self, d: Coord<I>) -> isize {
+        let (x, y): (isize, isize) = (d.x.to(), d.y.to());
+        y * self.stride as isize + x
+    }
+
+    pub fn coord_from_offset<I: FromPrimitive<isize>>(&self, o: usize) -> Coord<I> {
+        let (x, y) = (o % self.stride, o / self.stride);
+        coord(
+            I::from(x as isize - self.pad as isize),
+            I::from(y as isize - self.pad as isize),
+        )
+    }
+
+    pub fn view(&self) -> FieldView<'_, T> {
+        FieldView::new(
+            &self.data[self.pad * self.stride + self.pad..],
+            self.width,
+            self.stride,
+            self.height,
+        )
+    }
+}
+
+// Only Grid, not Field: data() and the offsets here cover the padding too, so the slice based helpers
+// go through view() instead
+impl<T> Grid for PaddedGrid<T> {
+    type Item = T;
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn cell(&self, x: usize, y: usize) -> &T {
+        &self.data[self.offset(x as isize, y as isize)]
+    }
+}
+
+impl<T> Index<usize> for PaddedGrid<T> {
+    type Output = T;
+    fn index(&self, offset: usize) -> &Self::Output {
+        &self.data[offset]
+    }
+}
+
+impl<T> IndexMut<usize> for PaddedGrid<T> {
+    fn index_mut(&mut self, offset: usize) -> &mut Self::Output {
+        &mut self.data[offset]
+    }
+}
+
+impl<T, I: PrimitiveInt + ToPrimitive<isize>> Index<(I, I)> for PaddedGrid<T> {
+    type Output = T;
+    fn index(&self, pos: (I, I)) -> &Self::Output {
+        &self.data[self.offset(pos.0, pos.1)]
+    }
+}
+
+impl<T, I: PrimitiveInt + ToPrimitive<isize>> IndexMut<(I, I)> for PaddedGrid<T> {
+    fn index_mut(&mut self, pos: (I, I)) -> &mut Self::Output {
+        let o = self.offset(pos.0, pos.1);
+        &mut self.data[o]
+    }
+}
+
+impl<T, I: PrimitiveInt + ToPrimitive<isize>> Index<Coord<I>> for PaddedGrid<T> {
+    type Output = T;
+    fn index(&self, pos: Coord<I>) -> &Self::Output {
+        &self.data[self.offset(pos.x, pos.y)]
+    }
+}
+
+impl<T, I: PrimitiveInt + ToPrimitive<isize>> IndexMut<Coord<I>> for PaddedGrid<T> {
+    fn index_mut(&mut self, pos: Coord<I>) -> &mut Self::Output {
+        let o = self.offset(pos.x, pos.y);
+        &mut self.data[o]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> PaddedGrid<u8> {
+        PaddedGrid::new(&FieldView::from(b"abc\ndef\n".as_slice()), 2, b'#')
+    }
+
+    #[test]
+    fn offsets_round_trip_inside_the_pad() {
+        let g = grid();
+        assert_eq!((g.stride(), g.pad(), g.data().len()), (7, 2, 42));
+        for y in -2..4 {
+            for x in -2..5 {
+                let o = g.offset(x, y);
+                assert_eq!(g.coord_from_offset::<i32>(o), coord(x, y));
+                assert_eq!(g.offset_coord(coord(x, y)), o);
+                let inside = (0..3).contains(&x) && (0..2).contains(&y);
+                assert_eq!(g[(x, y)] != b'#', inside);
+            }
+        }
+        assert_eq!(g.offset(-2, -2), 0);
+        assert_eq!(g.offset(4, 3), 41);
+        assert_eq!(
+            (g[(2, 1)], g[coord(0, 1)], *g.cell(1, 0)),
+            (b'f', b'd', b'b')
+        );
+    }
+
+    #[test]
+    fn view_skips_the_pad() {
+        let mut g = grid();
+        g[(1, 1)] = b'x';
+        let o = g.offset(-1, 0);
+        g[o] = b'y';
+        let v = g.view();
+        assert_eq!((v.width(), v.height(), v.stride()), (3, 2, 7));
+        assert_eq!(v.rows().collect::<Vec<_>>(), [b"abc", b"dxf"]);
+        assert_eq!(g.data()[o], b'y');
+    }
+
+    #[test]
+    fn deltas_step_between_offsets() {
+        let g = grid();
+        let o = g.offset(0, 0);
+        // Steps of up to `pad` cells in any direction stay inside the padding
+        for step in [
+            coord(1, 0),
+            coord(-1, -1),
+            coord(0, 2),
+            coord(-2, 1),
+            coord(2, -2),
+        ] {
+            let n = o.wrapping_add_signed(g.delta(step));
+            assert_eq!(g.coord_from_offset::<i32>(n), step);
+        }
+        assert_eq!(g.delta(coord(-2, 1)), 5);
+        assert_eq!(g.offset(-1, 1) as isize - g.offset(1, 0) as isize, 5);
+    }
+}
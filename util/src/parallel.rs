@@ -0,0 +1,214 @@
+use crate::{coord, Coord, Field, FieldMut, FieldView, FromPrimitive};
+use std::{
+    num::NonZeroUsize,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+// A pool of scoped std threads. Threads only live for the duration of a single call, jobs are handed
+// out dynamically through a shared counter, and results are always returned in job order.
+#[derive(Clone, Copy, Debug)]
+pub struct ScopedPool {
+    threads: usize,
+}
+
+impl Default for ScopedPool {
+    fn default() -> Self {
+        Self::new(thread::available_parallelism().map_or(1, NonZeroUsize::get))
+    }
+}
+
+impl ScopedPool {
+    pub fn new(threads: usize) -> Self {
+        Self {
+            threads: threads.max(1),
+        }
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    pub fn run<R: Send>(&self, jobs: usize, f: impl Fn(usize) -> R + Sync) -> Vec<R> {
+        self.run_with(jobs, || (), |_, i| f(i))
+    }
+
+    // Like `run`, but every worker thread gets its own scratch state created by `init`
+    pub fn run_with<S, R: Send>(
+        &self,
+        jobs: usize,
+        init: impl Fn() -> S + Sync,
+        f: impl Fn(&mut S, usize) -> R + Sync,
+    ) -> Vec<R> {
+        let threads = self.threads.min(jobs);
+        if threads <= 1 {
+            let mut state = init();
+            return (0..jobs).map(|i| f(&mut state, i)).collect();
+        }
+
+        let next = AtomicUsize::new(0);
+        let worker = || {
+            let mut state = init();
+            let mut done = Vec::new();
+            loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                if i >= jobs {
+                    break done;
+                }
+                done.push((i, f(&mut state, i)));
+            }
+        };
+
+        let mut results: Vec<Option<R>> = (0..jobs).map(|_| None).collect();
+        thread::scope(|s| {
+            let handles: Vec<_> = (0..threads).map(|_| s.spawn(worker)).collect();
+            for h in handles {
+                for (i, r) in h.join().unwrap() {
+                    results[i] = Some(r);
+                }
+            }
+        });
+        results.into_iter().map(Option::unwrap).collect()
+    }
+
+    // Hands every item out mutably. The items are split into one contiguous run per thread up front,
+    // so this suits jobs of similar cost.
+    pub fn for_each_mut<T: Send>(&self, items: &mut [T], f: impl Fn(&mut T) + Sync) {
+        let chunk = items.len().div_ceil(self.threads).max(1);
+        if chunk == items.len() {
+            items.iter_mut().for_each(f);
+            return;
+        }
+
+        let f = &f;
+        thread::scope(|s| {
+            for part in items.chunks_mut(chunk) {
+                s.spawn(move || part.iter_mut().for_each(f));
+            }
+        });
+    }
+
+    pub fn map<T: Sync, R: Send>(&self, items: &[T], f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+        self.map_with(items, || (), |_, t| f(t))
+    }
+
+    pub fn map_with<S, T: Sync, R: Send>(
+        &self,
+        items: &[T],
+        init: impl Fn() -> S + Sync,
+        f: impl Fn(&mut S, &T) -> R + Sync,
+    ) -> Vec<R> {
+        // A few chunks per thread so uneven work still balances out
+        let chunk = items.len().div_ceil(self.threads * 4).max(1);
+        let chunks: Vec<_> = items.chunks(chunk).collect();
+        self.run_with(chunks.len(), init, |s, i| {
+            chunks[i].iter().map(|t| f(s, t)).collect::<Vec<_>>()
+        })
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+pub trait ParField: Field + Sync
+where
+    Self::Item: Sync,
+{
+    fn par_rows<R: Send>(&self, f: impl Fn(usize, &[Self::Item]) -> R + Sync) -> Vec<R> {
+        ScopedPool::default().run(self.height(), |y| f(y, self.row(y)))
+    }
+
+    fn par_coords<I: FromPrimitive<usize>, R: Send>(
+        &self,
+        f: impl Fn(Coord<I>, &Self::Item) -> R + Sync,
+    ) -> Vec<R> {
+        self.par_rows(|y, row| {
+            row.iter()
+                .enumerate()
+                .map(|(x, c)| f(coord(I::from(x), I::from(y)), c))
+                .collect::<Vec<_>>()
+        })
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    // Splits the field into tiles of at most `tile_width` by `tile_height` cells, handing every tile to `f`
+    // along with the coordinate of its top left cell. Results are in row-major tile order.
+    fn par_tiles<R: Send>(
+        &self,
+        tile_width: usize,
+        tile_height: usize,
+        f: impl Fn(Coord<usize>, FieldView<'_, Self::Item>) -> R + Sync,
+    ) -> Vec<R> {
+        assert!(
+            tile_width > 0 && tile_height > 0,
+            "Tiles must be at least 1x1, got {tile_width}x{tile_height}"
+        );
+        let (w, h) = (self.width(), self.height());
+        let tiles_x = w.div_ceil(tile_width);
+        let tiles_y = h.div_ceil(tile_height);
+        ScopedPool::default().run(tiles_x * tiles_y, |i| {
+            let origin = coord(i % tiles_x * tile_width, i / tiles_x * tile_height);
+            let view = FieldView::new(
+                &self.data()[self.offset(origin.x, origin.y)..],
+                tile_width.min(w - origin.x),
+                self.stride(),
+                tile_height.min(h - origin.y),
+            );
+            f(origin, view)
+        })
+    }
+
+    fn par_rows_mut(&mut self, f: impl Fn(usize, &mut [Self::Item]) + Sync)
+    where
+        Self: FieldMut,
+        Self::Item: Send,
+    {
+        let (w, h, s) = (self.width(), self.height(), self.stride());
+        let mut rows: Vec<_> = self
+            .data_mut()
+            .chunks_mut(s)
+            .take(h)
+            .map(|row| &mut row[..w])
+            .enumerate()
+            .collect();
+        ScopedPool::default().for_each_mut(&mut rows, |(y, row)| f(*y, row));
+    }
+}
+
+impl<F: Field + Sync + ?Sized> ParField for F where F::Item: Sync {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FieldMutView, Grid};
+
+    #[test]
+    fn rows_mut_and_tiles() {
+        let mut f = FieldMutView::create_with_value(0u32, 3, 4, 50);
+        f.par_rows_mut(|y, row| row.iter_mut().for_each(|c| *c = y as u32));
+        assert!(f.rows().enumerate().all(|(y, r)| r == [y as u32; 3]));
+        // The stride gap isn't part of any row
+        assert!(f.data().iter().skip(3).step_by(4).all(|&c| c == 0));
+
+        let tiles = f.par_tiles(2, 20, |o, t| (o, t.width(), t.height()));
+        assert_eq!(
+            tiles,
+            [
+                (coord(0, 0), 2, 20),
+                (coord(2, 0), 1, 20),
+                (coord(0, 20), 2, 20),
+                (coord(2, 20), 1, 20),
+                (coord(0, 40), 2, 10),
+                (coord(2, 40), 1, 10)
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 1x1")]
+    fn empty_tiles() {
+        FieldMutView::create_with_value(0u8, 3, 3, 3).par_tiles(0, 1, |_, _| ());
+    }
+}
@@ -4,6 +4,8 @@ pub struct Parser<'a> {
     buf: &'a [u8],
 }
 
+// at_end is the emptiness check here, not is_empty
+#[allow(clippy::len_without_is_empty)]
 impl<'a> Parser<'a> {
     pub fn new(s: &'a str) -> Self {
         Self { buf: s.as_bytes() }
@@ -80,7 +82,7 @@ impl<'a> Parser<'a> {
     }
 
     pub fn peek_remainder(&mut self) -> &'a str {
-        to_str(&self.buf)
+        to_str(self.buf)
     }
 
     pub fn remainder(&mut self) -> &'a str {
@@ -0,0 +1,325 @@
+use crate::{coord, Coord, Field, FieldView, FromPrimitive, Grid, HashPair};
+use std::hash::{Hash, Hasher};
+
+// The 8 symmetries of a square grid. Rotations are clockwise as seen on screen, with y pointing down.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Transform {
+    Identity,
+    Rot90,
+    Rot180,
+    Rot270,
+    FlipX,
+    FlipY,
+    Transpose,
+    AntiTranspose,
+}
+
+impl Transform {
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity,
+        Transform::Rot90,
+        Transform::Rot180,
+        Transform::Rot270,
+        Transform::FlipX,
+        Transform::FlipY,
+        Transform::Transpose,
+        Transform::AntiTranspose,
+    ];
+
+    pub const ROTATIONS: [Transform; 4] = [
+        Transform::Identity,
+        Transform::Rot90,
+        Transform::Rot180,
+        Transform::Rot270,
+    ];
+
+    pub fn apply(self, c: Coord<isize>) -> Coord<isize> {
+        let Coord { x, y } = c;
+        match self {
+            Transform::Identity => coord(x, y),
+            Transform::Rot90 => coord(-y, x),
+            Transform::Rot180 => coord(-x, -y),
+            Transform::Rot270 => coord(y, -x),
+            Transform::FlipX => coord(-x, y),
+            Transform::FlipY => coord(x, -y),
+            Transform::Transpose => coord(y, x),
+            Transform::AntiTranspose => coord(-y, -x),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    Transform(Transform),
+    Direction(Coord<i32>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Match<I> {
+    pub pos: Coord<I>,
+    pub orientation: Orientation,
+}
+
+impl<I: HashPair> Hash for Match<I> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.pos.hash(state);
+        self.orientation.hash(state);
+    }
+}
+
+// The cells are relative to the pattern's key cell, the one the field is scanned for
+#[derive(Clone, Debug)]
+struct Variant {
+    cells: Vec<(Coord<isize>, u8)>,
+    anchor: Coord<isize>,
+    min: Coord<isize>,
+    max: Coord<isize>,
+    orientation: Orientation,
+}
+
+impl Variant {
+    fn new(cells: Vec<(Coord<isize>, u8)>, key: Option<usize>, orientation: Orientation) -> Self {
+        let key = key.map_or(coord(0, 0), |k| cells[k].0);
+        let cells: Vec<_> = cells.into_iter().map(|(c, b)| (c - key, b)).collect();
+        let anchor = -key;
+        let min = cells.iter().fold(anchor, |m, &(c, _)| m.component_min(c));
+        let max = cells.iter().fold(anchor, |m, &(c, _)| m.component_max(c));
+        Self {
+            cells,
+            anchor,
+            min,
+            max,
+            orientation,
+        }
+    }
+
+    // The cells relative to the top left of the bounding box, so variants that only differ in where
+    // their anchor ended up compare equal
+    fn normalized(&self) -> Vec<(Coord<isize>, u8)> {
+        let mut r: Vec<_> = self.cells.iter().map(|&(c, b)| (c - self.min, b)).collect();
+        r.sort_unstable();
+        r
+    }
+}
+
+// Matches a small pattern of bytes against a byte field. A match is reported at the position the
+// pattern's anchor ends up at: the top left cell of a template, or the first letter of a word.
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    variants: Vec<Variant>,
+    key: Option<u8>,
+}
+
+// The index of the cell with the rarest byte. Only positions holding that byte can start a match,
+// so the field is scanned for it once instead of trying every variant everywhere.
+fn key_cell(cells: &[(Coord<isize>, u8)]) -> Option<usize> {
+    let count = |b| cells.iter().filter(|&&(_, c)| c == b).count();
+    (0..cells.len()).min_by_key(|&i| count(cells[i].1))
+}
+
+impl Pattern {
+    // Every cell of `template` equal to `wildcard` matches anything. Transforms that map the template
+    // onto itself, or onto one of the earlier transforms, are skipped so every match is only reported once.
+    pub fn template(template: &[u8], wildcard: u8, transforms: &[Transform]) -> Self {
+        let t = FieldView::from(template);
+        let cells: Vec<_> = t
+            .coords::<isize>()
+            .map(|c| (c, *t.get(c.x, c.y)))
+            .filter(|&(_, b)| b != wildcard)
+            .collect();
+
+        let key = key_cell(&cells);
+        let mut variants: Vec<Variant> = Vec::new();
+        for &tr in transforms {
+            let v = Variant::new(
+                cells.iter().map(|&(c, b)| (tr.apply(c), b)).collect(),
+                key,
+                Orientation::Transform(tr),
+            );
+            let n = v.normalized();
+            if variants.iter().all(|o| o.normalized() != n) {
+                variants.push(v);
+            }
+        }
+        Self {
+            variants,
+            key: key.map(|k| cells[k].1),
+        }
+    }
+
+    pub fn word(word: &[u8], dirs: &[Coord<i32>]) -> Self {
+        let cells: Vec<_> = (0..).zip(word).map(|(i, &b)| (coord(i, 0), b)).collect();
+        let key = key_cell(&cells);
+        let variants = dirs
+            .iter()
+            .map(|&d| {
+                let d = coord(d.x as isize, d.y as isize);
+                Variant::new(
+                    cells.iter().map(|&(c, b)| (d * c.x, b)).collect(),
+                    key,
+                    Orientation::Direction(coord(d.x as i32, d.y as i32)),
+                )
+            })
+            .collect();
+        Self {
+            variants,
+            key: key.map(|k| cells[k].1),
+        }
+    }
+
+    // Calls `f` with the position of the key cell and the variant of every match
+    fn scan<F>(&self, field: &F, mut f: impl FnMut(Coord<isize>, &Variant))
+    where
+        F: Field<Item = u8> + ?Sized,
+    {
+        let (w, h, s) = (
+            field.width() as isize,
+            field.height() as isize,
+            field.stride() as isize,
+        );
+        let data = field.data();
+        // Per variant the cells besides the key as offsets into the data, and the key positions that
+        // keep the whole variant inside the field
+        let variants: Vec<_> = self
+            .variants
+            .iter()
+            .map(|v| {
+                let cells: Vec<_> = v
+                    .cells
+                    .iter()
+                    .filter(|&&(c, b)| c != coord(0, 0) || Some(b) != self.key)
+                    .map(|&(c, b)| (c.y * s + c.x, b))
+                    .collect();
+                (v, cells, -v.min, coord(w, h) - v.max)
+            })
+            .collect();
+
+        for y in 0..h {
+            let row = y * s;
+            let bytes = &data[row as usize..(row + w) as usize];
+            let mut x = 0;
+            while x < w {
+                if let Some(k) = self.key {
+                    match bytes[x as usize..].iter().position(|&b| b == k) {
+                        Some(d) => x += d as isize,
+                        None => break,
+                    }
+                }
+                // Checked without short circuiting, as the branches mispredict on a field of letters
+                for (v, cells, lo, hi) in &variants {
+                    let fits = (x >= lo.x) & (x < hi.x) & (y >= lo.y) & (y < hi.y);
+                    let i = row + x;
+                    if fits
+                        && cells
+                            .iter()
+                            .fold(true, |ok, &(o, b)| ok & (data[(i + o) as usize] == b))
+                    {
+                        f(coord(x, y), v);
+                    }
+                }
+                x += 1;
+            }
+        }
+    }
+
+    pub fn matches<F, I>(&self, field: &F) -> impl Iterator<Item = Match<I>>
+    where
+        F: Field<Item = u8> + ?Sized,
+        I: FromPrimitive<usize>,
+    {
+        let mut r = Vec::new();
+        self.scan(field, |p, v| {
+            let pos = p + v.anchor;
+            r.push(Match {
+                pos: coord(I::from(pos.x as usize), I::from(pos.y as usize)),
+                orientation: v.orientation,
+            });
+        });
+        r.into_iter()
+    }
+
+    pub fn count<F: Field<Item = u8> + ?Sized>(&self, field: &F) -> usize {
+        let mut n = 0;
+        self.scan(field, |_, _| n += 1);
+        n
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(mut v: Vec<Match<usize>>) -> Vec<(Coord<usize>, Orientation)> {
+        let mut r: Vec<_> = v.drain(..).map(|m| (m.pos, m.orientation)).collect();
+        r.sort_unstable_by_key(|&(p, _)| (p.y, p.x));
+        r
+    }
+
+    #[test]
+    fn word_at_the_edges() {
+        let f = FieldView::from(b"XMASS\nMM..A\nA.A.M\nS..SX\n".as_slice());
+        let p = Pattern::word(b"XMAS", &coord(0, 0).neighbors8());
+        let dirs: Vec<_> = p
+            .matches::<_, usize>(&f)
+            .filter(|m| m.pos == coord(0, 0))
+            .map(|m| m.orientation)
+            .collect();
+        assert_eq!(dirs.len(), 3);
+        for d in [coord(1, 0), coord(0, 1), coord(1, 1)] {
+            assert!(dirs.contains(&Orientation::Direction(d)));
+        }
+        // Upwards along the right edge
+        let up: Vec<_> = p.matches(&f).filter(|m| m.pos == coord(4, 3)).collect();
+        assert_eq!(
+            up,
+            [Match {
+                pos: coord(4_usize, 3),
+                orientation: Orientation::Direction(coord(0, -1)),
+            }]
+        );
+        assert_eq!(p.count(&f), 4);
+
+        // Running off the field is no match
+        let f = FieldView::from(b"XMA\nMAS\n".as_slice());
+        assert_eq!(p.count(&f), 0);
+    }
+
+    #[test]
+    fn template_transforms() {
+        // A B      B A
+        // . C  ->  . C  is the template mirrored in x
+        let f = FieldView::from(b".....\n.BA..\n.C...\n.....\n".as_slice());
+        let p = Pattern::template(b"AB\n.C", b'.', &Transform::ALL);
+        assert_eq!(
+            sorted(p.matches(&f).collect()),
+            [(coord(2, 1), Orientation::Transform(Transform::FlipX))]
+        );
+        let p = Pattern::template(b"AB\n.C", b'.', &Transform::ROTATIONS);
+        assert_eq!(p.count(&f), 0);
+
+        // Rotated a quarter turn, the anchor ends up in the top right corner
+        let f = FieldView::from(b".A\nCB\n".as_slice());
+        assert_eq!(
+            sorted(p.matches(&f).collect()),
+            [(coord(1, 0), Orientation::Transform(Transform::Rot90))]
+        );
+    }
+
+    #[test]
+    fn template_wildcards() {
+        let p = Pattern::template(b"A.B", b'.', &[Transform::Identity]);
+        let f = FieldView::from(b"AxB\nAAB\nA.B\nABx\n".as_slice());
+        assert_eq!(
+            sorted(p.matches(&f).collect())
+                .iter()
+                .map(|&(p, _)| p)
+                .collect::<Vec<_>>(),
+            [coord(0, 0), coord(0, 1), coord(0, 2)]
+        );
+
+        // Symmetric variants are only reported once
+        let p = Pattern::template(b"A.A", b'.', &Transform::ROTATIONS);
+        let f = FieldView::from(b"A.A\n...\nA..\n".as_slice());
+        assert_eq!(p.count(&f), 2);
+    }
+}
@@ -1,11 +1,20 @@
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
 pub trait Primitive: Copy {}
 pub trait PrimitiveInt: Primitive {}
 pub trait PrimitiveFloat: Primitive {}
 
-pub trait ToPrimitive<T: Primitive> {
+pub trait ToPrimitive<T: Primitive>: TryToPrimitive<T> {
     fn to(self) -> T;
 }
 
+// Like ToPrimitive, but returns None instead of wrapping when the value doesn't fit into T. Floats
+// are truncated towards zero like with `as`, and only fail when out of range or NaN.
+pub trait TryToPrimitive<T: Primitive> {
+    fn try_to(self) -> Option<T>;
+}
+
 pub trait FromPrimitive<T: Primitive> {
     fn from(value: T) -> Self;
 }
@@ -16,6 +25,28 @@ impl<T: Primitive, U: ToPrimitive<T> + Primitive> FromPrimitive<U> for T {
     }
 }
 
+// TryToPrimitive seen from the target type, so generic code can name it in a bound on T. Not called
+// try_from, which would clash with TryFrom on concrete types.
+pub trait TryFromPrimitive<T: Primitive>: Sized {
+    fn try_from_primitive(value: T) -> Option<Self>;
+}
+
+impl<T: Primitive, U: TryToPrimitive<T> + Primitive> TryFromPrimitive<U> for T {
+    fn try_from_primitive(value: U) -> Option<Self> {
+        value.try_to()
+    }
+}
+
+pub trait Signed: Primitive + Neg<Output = Self> {
+    fn abs(self) -> Self;
+    fn signum(self) -> Self;
+}
+
+pub trait Euclid: Primitive {
+    fn div_euclid(self, rhs: Self) -> Self;
+    fn rem_euclid(self, rhs: Self) -> Self;
+}
+
 pub trait Increment: Copy {
     fn pre_inc(&mut self) -> Self;
     fn post_inc(&mut self) -> Self;
@@ -66,17 +97,78 @@ macro_rules! impl_increments {
 
 impl_increments!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
 
+// With the validation feature, every conversion checks that the value survives it, so that e.g. a
+// negative coordinate doesn't silently turn into a huge offset
 macro_rules! impl_to_primitive {
     ($t:ty => $($u:ty),+) => {
         $(
             impl ToPrimitive<$u> for $t {
                 fn to(self) -> $u {
+                    #[cfg(feature = "validation")]
+                    if TryToPrimitive::<$u>::try_to(self).is_none() {
+                        panic!(
+                            "Validation failed! Lossy conversion of {self} from {} to {}",
+                            stringify!($t),
+                            stringify!($u)
+                        );
+                    }
                     self as $u
                 }
             }
         )+
     };
 }
+
+macro_rules! impl_try_to_primitive {
+    (int $t:ty) => {
+        impl_try_to_primitive!($t => try_from i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+        impl_try_to_primitive!($t => as f32, f64);
+    };
+    (float $t:ty) => {
+        impl_try_to_primitive!($t => trunc i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+        impl_try_to_primitive!($t => as_finite f32, f64);
+    };
+    ($t:ty => try_from $($u:ty),+) => {
+        $(
+            impl TryToPrimitive<$u> for $t {
+                fn try_to(self) -> Option<$u> {
+                    <$u>::try_from(self).ok()
+                }
+            }
+        )+
+    };
+    ($t:ty => as $($u:ty),+) => {
+        $(
+            impl TryToPrimitive<$u> for $t {
+                fn try_to(self) -> Option<$u> {
+                    Some(self as $u)
+                }
+            }
+        )+
+    };
+    ($t:ty => trunc $($u:ty),+) => {
+        $(
+            impl TryToPrimitive<$u> for $t {
+                fn try_to(self) -> Option<$u> {
+                    // The valid range is MIN..2^bits, and both ends are exact powers of two as floats
+                    let bits = <$u>::BITS as i32 - (<$u>::MIN != 0) as i32;
+                    let t = self.trunc();
+                    (t >= <$u>::MIN as $t && t < (2.0 as $t).powi(bits)).then_some(self as $u)
+                }
+            }
+        )+
+    };
+    ($t:ty => as_finite $($u:ty),+) => {
+        $(
+            impl TryToPrimitive<$u> for $t {
+                fn try_to(self) -> Option<$u> {
+                    let r = self as $u;
+                    (r.is_finite() || !self.is_finite()).then_some(r)
+                }
+            }
+        )+
+    };
+}
 macro_rules! impl_primitive {
     ($($t:ty),+) => {
         $(
@@ -90,6 +182,7 @@ macro_rules! impl_primitive_int {
     ($($t:ty),+) => {
         $(
             impl PrimitiveInt for $t {}
+            impl_try_to_primitive!(int $t);
         )+
     };
 }
@@ -98,6 +191,7 @@ macro_rules! impl_primitive_float {
     ($($t:ty),+) => {
         $(
             impl PrimitiveFloat for $t {}
+            impl_try_to_primitive!(float $t);
         )+
     };
 }
@@ -105,3 +199,162 @@ macro_rules! impl_primitive_float {
 impl_primitive!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
 impl_primitive_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
 impl_primitive_float!(f32, f64);
+
+macro_rules! impl_signed {
+    ($($t:ty),+) => {
+        $(
+            impl Signed for $t {
+                fn abs(self) -> Self {
+                    <$t>::abs(self)
+                }
+                fn signum(self) -> Self {
+                    <$t>::signum(self)
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! impl_euclid {
+    ($($t:ty),+) => {
+        $(
+            impl Euclid for $t {
+                fn div_euclid(self, rhs: Self) -> Self {
+                    <$t>::div_euclid(self, rhs)
+                }
+                fn rem_euclid(self, rhs: Self) -> Self {
+                    <$t>::rem_euclid(self, rhs)
+                }
+            }
+        )+
+    };
+}
+
+impl_signed!(i8, i16, i32, i64, i128, isize, f32, f64);
+impl_euclid!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+// The integer arithmetic that num, geom and checked build on. Every primitive integer implements it,
+// signed or not; code that needs negative values asks for Signed on top. Conversions go through i128,
+// so values must fit into that.
+pub trait Int:
+    PrimitiveInt
+    + Ord
+    + Default
+    + fmt::Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+    + ToPrimitive<i128>
+    + FromPrimitive<i128>
+    + TryFromPrimitive<i128>
+{
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_int {
+    ($($t:ty),+) => {
+        $(
+            impl Int for $t {
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+            }
+        )+
+    };
+}
+
+impl_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_to_signed_unsigned_edges() {
+        assert_eq!(TryToPrimitive::<u8>::try_to(-1_i32), None);
+        assert_eq!(TryToPrimitive::<u8>::try_to(255_i32), Some(255_u8));
+        assert_eq!(TryToPrimitive::<u8>::try_to(256_i32), None);
+        assert_eq!(TryToPrimitive::<i8>::try_to(128_u8), None);
+        assert_eq!(TryToPrimitive::<i8>::try_to(127_u8), Some(127_i8));
+        assert_eq!(TryToPrimitive::<i8>::try_to(-128_i64), Some(i8::MIN));
+        assert_eq!(TryToPrimitive::<i8>::try_to(-129_i64), None);
+        assert_eq!(
+            TryToPrimitive::<u64>::try_to(u64::MAX as u128),
+            Some(u64::MAX)
+        );
+        assert_eq!(TryToPrimitive::<i64>::try_to(u64::MAX), None);
+        assert_eq!(TryToPrimitive::<usize>::try_to(-1_isize), None);
+    }
+
+    #[test]
+    fn try_to_i128_extremes() {
+        assert_eq!(TryToPrimitive::<i128>::try_to(i128::MIN), Some(i128::MIN));
+        assert_eq!(TryToPrimitive::<u128>::try_to(i128::MIN), None);
+        assert_eq!(
+            TryToPrimitive::<u128>::try_to(i128::MAX),
+            Some(i128::MAX as u128)
+        );
+        assert_eq!(TryToPrimitive::<i128>::try_to(u128::MAX), None);
+        assert_eq!(TryToPrimitive::<i64>::try_to(i128::MAX), None);
+        assert_eq!(
+            <i8 as TryFromPrimitive<i128>>::try_from_primitive(200),
+            None
+        );
+        assert_eq!(
+            <u32 as TryFromPrimitive<i128>>::try_from_primitive(200),
+            Some(200)
+        );
+    }
+
+    #[test]
+    fn try_to_float_ranges() {
+        // Truncated towards zero, so anything above -1 fits into an unsigned type
+        assert_eq!(TryToPrimitive::<u8>::try_to(-0.5_f64), Some(0));
+        assert_eq!(TryToPrimitive::<u8>::try_to(-1.0_f64), None);
+        assert_eq!(TryToPrimitive::<u8>::try_to(255.9_f64), Some(255));
+        assert_eq!(TryToPrimitive::<u8>::try_to(256.0_f64), None);
+        assert_eq!(TryToPrimitive::<i8>::try_to(-128.9_f32), Some(-128));
+        assert_eq!(TryToPrimitive::<i8>::try_to(-129.0_f32), None);
+        assert_eq!(
+            TryToPrimitive::<i64>::try_to(-(2.0_f64.powi(63))),
+            Some(i64::MIN)
+        );
+        assert_eq!(TryToPrimitive::<i64>::try_to(2.0_f64.powi(63)), None);
+        assert_eq!(TryToPrimitive::<u64>::try_to(f32::MAX), None);
+        assert_eq!(TryToPrimitive::<i32>::try_to(f64::NAN), None);
+        assert_eq!(TryToPrimitive::<i32>::try_to(f64::INFINITY), None);
+        assert_eq!(TryToPrimitive::<i32>::try_to(f64::NEG_INFINITY), None);
+    }
+
+    #[test]
+    fn try_to_between_floats() {
+        assert_eq!(TryToPrimitive::<f32>::try_to(1.5_f64), Some(1.5));
+        assert_eq!(TryToPrimitive::<f32>::try_to(f64::MAX), None);
+        assert_eq!(
+            TryToPrimitive::<f32>::try_to(f64::INFINITY),
+            Some(f32::INFINITY)
+        );
+        assert!(TryToPrimitive::<f32>::try_to(f64::NAN).unwrap().is_nan());
+        assert_eq!(
+            TryToPrimitive::<f64>::try_to(u128::MAX),
+            Some(u128::MAX as f64)
+        );
+    }
+
+    #[cfg(feature = "validation")]
+    #[test]
+    #[should_panic(expected = "Lossy conversion")]
+    fn to_validates() {
+        let _: u8 = (-1_i32).to();
+    }
+}
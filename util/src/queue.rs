@@ -0,0 +1,338 @@
+// What Dijkstra style searches need from a priority queue, so the same search code can run on a
+// BucketQueue when the step costs are small and bounded, or on an IndexedHeap otherwise
+pub trait MinQueue<T> {
+    fn push(&mut self, cost: usize, item: T);
+    fn pop(&mut self) -> Option<(usize, T)>;
+    fn is_empty(&self) -> bool;
+}
+
+// A bucket queue for Dial's algorithm. Costs must never go below the cost of the last popped item and
+// never more than `max_step` above it, which holds for Dijkstra when no edge costs more than
+// `max_step`. Push and pop are constant time, apart from skipping over empty buckets.
+#[derive(Clone, Debug)]
+pub struct BucketQueue<T> {
+    buckets: Vec<Vec<T>>,
+    current: usize,
+    len: usize,
+}
+
+impl<T> BucketQueue<T> {
+    pub fn new(max_step: usize) -> Self {
+        Self {
+            buckets: std::iter::repeat_with(Vec::new)
+                .take(max_step + 1)
+                .collect(),
+            current: 0,
+            len: 0,
+        }
+    }
+
+    pub fn push(&mut self, cost: usize, item: T) {
+        assert!(
+            cost >= self.current && cost - self.current < self.buckets.len(),
+            "Cost {cost} outside of the window starting at {}",
+            self.current
+        );
+        let span = self.buckets.len();
+        self.buckets[cost % span].push(item);
+        self.len += 1;
+    }
+
+    // Items with the same cost come out in no particular order
+    pub fn pop(&mut self) -> Option<(usize, T)> {
+        if self.len == 0 {
+            return None;
+        }
+        let span = self.buckets.len();
+        loop {
+            if let Some(item) = self.buckets[self.current % span].pop() {
+                self.len -= 1;
+                return Some((self.current, item));
+            }
+            self.current += 1;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<T> MinQueue<T> for BucketQueue<T> {
+    fn push(&mut self, cost: usize, item: T) {
+        BucketQueue::push(self, cost, item);
+    }
+
+    fn pop(&mut self) -> Option<(usize, T)> {
+        BucketQueue::pop(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        BucketQueue::is_empty(self)
+    }
+}
+
+const ABSENT: usize = usize::MAX;
+
+// A binary min-heap over the items 0..len, which knows where every item sits so its priority can be
+// lowered in place instead of pushing duplicates
+#[derive(Clone, Debug)]
+pub struct IndexedHeap<P> {
+    heap: Vec<(P, usize)>,
+    pos: Vec<usize>,
+}
+
+impl<P: Ord + Copy> IndexedHeap<P> {
+    pub fn new(len: usize) -> Self {
+        Self {
+            heap: Vec::new(),
+            pos: vec![ABSENT; len],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn contains(&self, item: usize) -> bool {
+        self.pos[item] != ABSENT
+    }
+
+    pub fn priority(&self, item: usize) -> Option<P> {
+        self.contains(item).then(|| self.heap[self.pos[item]].0)
+    }
+
+    pub fn push(&mut self, item: usize, priority: P) {
+        assert!(!self.contains(item), "Item {item} is already queued");
+        self.pos[item] = self.heap.len();
+        self.heap.push((priority, item));
+        self.sift_up(self.heap.len() - 1);
+    }
+
+    pub fn decrease_key(&mut self, item: usize, priority: P) {
+        let i = self.pos[item];
+        assert!(i != ABSENT, "Item {item} is not queued");
+        assert!(
+            priority <= self.heap[i].0,
+            "Priority of item {item} would increase"
+        );
+        self.heap[i].0 = priority;
+        self.sift_up(i);
+    }
+
+    // Queues the item, or lowers its priority if it is queued with a higher one. Returns whether
+    // anything changed, which is exactly when Dijkstra found a better path.
+    pub fn push_or_decrease(&mut self, item: usize, priority: P) -> bool {
+        match self.priority(item) {
+            None => self.push(item, priority),
+            Some(p) if priority < p => self.decrease_key(item, priority),
+            Some(_) => return false,
+        }
+        true
+    }
+
+    pub fn peek(&self) -> Option<(usize, P)> {
+        self.heap.first().map(|&(p, item)| (item, p))
+    }
+
+    pub fn pop(&mut self) -> Option<(usize, P)> {
+        if self.heap.is_empty() {
+            return None;
+        }
+        let (p, item) = self.heap.swap_remove(0);
+        self.pos[item] = ABSENT;
+        if !self.heap.is_empty() {
+            self.pos[self.heap[0].1] = 0;
+            self.sift_down(0);
+        }
+        Some((item, p))
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.pos[self.heap[a].1] = a;
+        self.pos[self.heap[b].1] = b;
+    }
+
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if self.heap[parent].0 <= self.heap[i].0 {
+                break;
+            }
+            self.swap(i, parent);
+            i = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        loop {
+            let (l, r) = (2 * i + 1, 2 * i + 2);
+            let mut min = i;
+            if l < self.heap.len() && self.heap[l].0 < self.heap[min].0 {
+                min = l;
+            }
+            if r < self.heap.len() && self.heap[r].0 < self.heap[min].0 {
+                min = r;
+            }
+            if min == i {
+                break;
+            }
+            self.swap(i, min);
+            i = min;
+        }
+    }
+}
+
+// Items are node indices here, and pushing a node that is already queued only ever lowers its cost
+impl MinQueue<usize> for IndexedHeap<usize> {
+    fn push(&mut self, cost: usize, item: usize) {
+        self.push_or_decrease(item, cost);
+    }
+
+    fn pop(&mut self) -> Option<(usize, usize)> {
+        IndexedHeap::pop(self).map(|(item, cost)| (cost, item))
+    }
+
+    fn is_empty(&self) -> bool {
+        IndexedHeap::is_empty(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain<Q: MinQueue<usize>>(q: &mut Q) -> Vec<(usize, usize)> {
+        std::iter::from_fn(|| q.pop()).collect()
+    }
+
+    #[test]
+    fn bucket_pop_order() {
+        let mut q = BucketQueue::new(3);
+        for (cost, item) in [(2, 20), (0, 0), (3, 30), (1, 10), (3, 31)] {
+            q.push(cost, item);
+        }
+        assert_eq!(q.len(), 5);
+        assert_eq!(q.pop(), Some((0, 0)));
+        assert_eq!(q.pop(), Some((1, 10)));
+        // The window moved along, so cost 4 fits now and shares a bucket with cost 0
+        q.push(4, 40);
+        q.push(1, 11);
+        assert_eq!(q.pop(), Some((1, 11)));
+        let rest = drain(&mut q);
+        assert_eq!(rest.iter().map(|r| r.0).collect::<Vec<_>>(), [2, 3, 3, 4]);
+        assert!(q.is_empty() && q.pop().is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Cost 4 outside of the window starting at 0")]
+    fn bucket_window_above() {
+        BucketQueue::new(3).push(4, ());
+    }
+
+    #[test]
+    #[should_panic(expected = "Cost 1 outside of the window starting at 2")]
+    fn bucket_window_below() {
+        let mut q = BucketQueue::new(3);
+        q.push(2, ());
+        q.pop();
+        q.push(1, ());
+    }
+
+    #[test]
+    fn heap_sifts() {
+        // Pseudo random priorities with plenty of duplicates
+        let mut x = 12345_u64;
+        let mut prios = Vec::new();
+        let mut q = IndexedHeap::new(200);
+        for item in 0..200 {
+            x = x
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let p = (x >> 33) % 50;
+            prios.push(p);
+            q.push(item, p);
+        }
+        // Lower every third one, which sifts it up from wherever it sits
+        for item in (0..200).step_by(3) {
+            prios[item] /= 2;
+            q.decrease_key(item, prios[item]);
+        }
+        assert_eq!(q.len(), 200);
+        let mut popped = Vec::new();
+        while let Some((item, p)) = q.pop() {
+            assert_eq!(p, prios[item]);
+            assert!(!q.contains(item));
+            popped.push((p, item));
+        }
+        assert!(popped.is_sorted_by_key(|&(p, _)| p));
+        popped.sort_unstable_by_key(|&(_, item)| item);
+        assert!(popped.iter().map(|&(_, item)| item).eq(0..200));
+    }
+
+    #[test]
+    fn heap_decrease_and_repush() {
+        let mut q = IndexedHeap::new(4);
+        q.push(0, 5);
+        q.push(1, 7);
+        q.push(2, 9);
+        assert_eq!(q.peek(), Some((0, 5)));
+        q.decrease_key(2, 1);
+        assert_eq!((q.peek(), q.priority(2)), (Some((2, 1)), Some(1)));
+
+        assert!(!q.push_or_decrease(1, 7));
+        assert!(!q.push_or_decrease(1, 8));
+        assert!(q.push_or_decrease(1, 0));
+        assert!(q.push_or_decrease(3, 6));
+        assert_eq!(q.pop(), Some((1, 0)));
+        assert_eq!(q.priority(1), None);
+
+        // Popped items can be queued again
+        q.push(1, 4);
+        assert_eq!(
+            std::iter::from_fn(|| q.pop()).collect::<Vec<_>>(),
+            [(2, 1), (1, 4), (0, 5), (3, 6)]
+        );
+        assert!(q.is_empty() && !q.contains(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Item 1 is not queued")]
+    fn heap_decrease_absent() {
+        let mut q = IndexedHeap::new(2);
+        q.push(0, 1);
+        q.decrease_key(1, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Priority of item 0 would increase")]
+    fn heap_decrease_upwards() {
+        let mut q = IndexedHeap::new(1);
+        q.push(0, 1);
+        q.decrease_key(0, 2);
+    }
+
+    #[test]
+    fn min_queues_agree() {
+        let pushes = [(3, 1), (5, 2), (4, 3), (2, 1), (6, 4), (4, 0)];
+        let mut bucket = BucketQueue::new(10);
+        let mut heap = IndexedHeap::new(5);
+        for (cost, item) in pushes {
+            MinQueue::push(&mut bucket, cost, item);
+            MinQueue::push(&mut heap, cost, item);
+        }
+        // The heap keeps one entry per item, the bucket queue keeps the duplicate too
+        assert_eq!(drain(&mut heap), [(2, 1), (4, 0), (4, 3), (5, 2), (6, 4)]);
+        let costs: Vec<_> = drain(&mut bucket).iter().map(|r| r.0).collect();
+        assert_eq!(costs, [2, 3, 4, 4, 5, 6]);
+    }
+}
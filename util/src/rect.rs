@@ -0,0 +1,179 @@
+use crate::{coord, Coord, FromPrimitive, HashPair, Increment};
+use std::hash::{Hash, Hasher};
+use std::ops::{Add, Div, Sub};
+
+// An axis aligned rectangle covering `min.x..max.x` by `min.y..max.y`, so `max` is exclusive
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rect<T> {
+    pub min: Coord<T>,
+    pub max: Coord<T>,
+}
+
+impl<T: HashPair> Hash for Rect<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.min.hash(state);
+        self.max.hash(state);
+    }
+}
+
+impl<T> Rect<T> {
+    pub fn new(min: Coord<T>, max: Coord<T>) -> Self {
+        Self { min, max }
+    }
+}
+
+impl<T> Rect<T>
+where
+    T: Copy + Ord + Add<Output = T> + Sub<Output = T> + Div<Output = T> + FromPrimitive<i32>,
+{
+    pub fn from_size(size: Coord<T>) -> Self {
+        let zero = T::from(0);
+        Self::new(coord(zero, zero), size)
+    }
+
+    pub fn from_origin_size(origin: Coord<T>, size: Coord<T>) -> Self {
+        Self::new(origin, origin + size)
+    }
+
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y
+    }
+
+    pub fn size(&self) -> Coord<T> {
+        self.max - self.min
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.x >= self.max.x || self.min.y >= self.max.y
+    }
+
+    pub fn contains(&self, p: Coord<T>) -> bool {
+        (self.min.x..self.max.x).contains(&p.x) && (self.min.y..self.max.y).contains(&p.y)
+    }
+
+    pub fn intersect(&self, other: &Self) -> Option<Self> {
+        let r = Self::new(
+            self.min.component_max(other.min),
+            self.max.component_min(other.max),
+        );
+        (!r.is_empty()).then_some(r)
+    }
+
+    // The smallest rect containing both
+    pub fn union(&self, other: &Self) -> Self {
+        Self::new(
+            self.min.component_min(other.min),
+            self.max.component_max(other.max),
+        )
+    }
+
+    // Splits into top left, top right, bottom left and bottom right. A middle row or column that
+    // can't be divided evenly is not part of any quadrant.
+    pub fn quadrants(&self) -> [Self; 4] {
+        let two = T::from(2);
+        let half = self.size() / two;
+        let lo = self.min + half;
+        let hi = self.max - half;
+        [
+            Self::new(self.min, lo),
+            Self::new(coord(hi.x, self.min.y), coord(self.max.x, lo.y)),
+            Self::new(coord(self.min.x, hi.y), coord(lo.x, self.max.y)),
+            Self::new(hi, self.max),
+        ]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Coord<T>>
+    where
+        T: Increment,
+    {
+        let (min, max) = (self.min, self.max);
+        let ys = std::iter::successors(Some(min.y), |y| Some(y.get_inc()))
+            .take_while(move |&y| y < max.y);
+        ys.flat_map(move |y| {
+            std::iter::successors(Some(min.x), |x| Some(x.get_inc()))
+                .take_while(move |&x| x < max.x)
+                .map(move |x| coord(x, y))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x0: i32, y0: i32, x1: i32, y1: i32) -> Rect<i32> {
+        Rect::new(coord(x0, y0), coord(x1, y1))
+    }
+
+    #[test]
+    fn contains() {
+        let r = Rect::from_origin_size(coord(-2, 1), coord(3, 2));
+        assert_eq!(r, rect(-2, 1, 1, 3));
+        assert_eq!((r.width(), r.height(), r.size()), (3, 2, coord(3, 2)));
+        assert!(r.contains(coord(-2, 1)) && r.contains(coord(0, 2)));
+        // max is exclusive
+        assert!(!r.contains(coord(1, 2)) && !r.contains(coord(0, 3)));
+        assert!(!r.contains(coord(-3, 1)) && !r.contains(coord(-2, 0)));
+        assert_eq!(
+            r.iter().collect::<Vec<_>>(),
+            [
+                coord(-2, 1),
+                coord(-1, 1),
+                coord(0, 1),
+                coord(-2, 2),
+                coord(-1, 2),
+                coord(0, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn empty() {
+        for r in [rect(0, 0, 0, 5), rect(0, 0, 5, 0), rect(3, 3, 1, 5)] {
+            assert!(r.is_empty(), "{r:?}");
+            assert!(!r.contains(r.min));
+            assert_eq!(r.iter().count(), 0);
+        }
+        assert!(Rect::<i32>::default().is_empty());
+        assert!(!rect(0, 0, 1, 1).is_empty());
+    }
+
+    #[test]
+    fn intersection_and_union() {
+        let a = rect(0, 0, 4, 3);
+        assert_eq!(a.intersect(&rect(2, 1, 6, 6)), Some(rect(2, 1, 4, 3)));
+        assert_eq!(a.intersect(&rect(1, 1, 2, 2)), Some(rect(1, 1, 2, 2)));
+        // Touching edges share no cells
+        assert_eq!(a.intersect(&rect(4, 0, 6, 3)), None);
+        assert_eq!(a.intersect(&rect(0, 3, 4, 5)), None);
+        assert_eq!(a.intersect(&rect(5, 5, 6, 6)), None);
+        assert_eq!(a.intersect(&rect(1, 1, 1, 2)), None);
+        assert_eq!(a.union(&rect(5, -1, 6, 1)), rect(0, -1, 6, 3));
+    }
+
+    #[test]
+    fn quadrants() {
+        // An odd size leaves out the middle row and column
+        let q = Rect::from_size(coord(11, 7)).quadrants();
+        assert_eq!(
+            q,
+            [
+                rect(0, 0, 5, 3),
+                rect(6, 0, 11, 3),
+                rect(0, 4, 5, 7),
+                rect(6, 4, 11, 7)
+            ]
+        );
+        assert!(q
+            .iter()
+            .all(|r| !r.contains(coord(5, 1)) && !r.contains(coord(1, 3))));
+        let q = rect(2, 2, 6, 4).quadrants();
+        assert_eq!(q[0], rect(2, 2, 4, 3));
+        assert_eq!(q[3], rect(4, 3, 6, 4));
+        assert_eq!(q.iter().map(|r| r.iter().count()).sum::<usize>(), 8);
+    }
+}
@@ -0,0 +1,192 @@
+use crate::{Coord, Grid, PrimitiveInt, ToPrimitive};
+use std::{
+    fmt::{Display, Formatter, Write},
+    io::BufRead,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Color {
+    Black = 30,
+    Red = 31,
+    Green = 32,
+    Yellow = 33,
+    Blue = 34,
+    Magenta = 35,
+    Cyan = 36,
+    White = 37,
+    BrightBlack = 90,
+    BrightRed = 91,
+    BrightGreen = 92,
+    BrightYellow = 93,
+    BrightBlue = 94,
+    BrightMagenta = 95,
+    BrightCyan = 96,
+    BrightWhite = 97,
+}
+
+impl Color {
+    pub fn ansi_code(self) -> u8 {
+        self as u8
+    }
+}
+
+struct Overlay {
+    offsets: Vec<usize>,
+    ch: Option<char>,
+    color: Option<Color>,
+}
+
+pub struct FieldRender<'f, F: ?Sized, M> {
+    field: &'f F,
+    map: M,
+    overlays: Vec<Overlay>,
+}
+
+impl<'f, F, M> FieldRender<'f, F, M>
+where
+    F: Grid + ?Sized,
+    M: Fn(&F::Item) -> char,
+{
+    pub fn new(field: &'f F, map: M) -> Self {
+        Self {
+            field,
+            map,
+            overlays: Vec::new(),
+        }
+    }
+
+    pub fn mark<I: PrimitiveInt + ToPrimitive<usize>>(
+        self,
+        coords: impl IntoIterator<Item = Coord<I>>,
+        ch: char,
+    ) -> Self {
+        self.overlay(coords, Some(ch), None)
+    }
+
+    pub fn highlight<I: PrimitiveInt + ToPrimitive<usize>>(
+        self,
+        coords: impl IntoIterator<Item = Coord<I>>,
+        color: Color,
+    ) -> Self {
+        self.overlay(coords, None, Some(color))
+    }
+
+    pub fn mark_colored<I: PrimitiveInt + ToPrimitive<usize>>(
+        self,
+        coords: impl IntoIterator<Item = Coord<I>>,
+        ch: char,
+        color: Color,
+    ) -> Self {
+        self.overlay(coords, Some(ch), Some(color))
+    }
+
+    fn overlay<I: PrimitiveInt + ToPrimitive<usize>>(
+        mut self,
+        coords: impl IntoIterator<Item = Coord<I>>,
+        ch: Option<char>,
+        color: Option<Color>,
+    ) -> Self {
+        let offsets = coords
+            .into_iter()
+            .filter(|&c| self.field.in_bounds_coord(c))
+            .map(|c| c.y.to() * self.field.width() + c.x.to())
+            .collect();
+        self.overlays.push(Overlay { offsets, ch, color });
+        self
+    }
+}
+
+impl<'f, F, M> Display for FieldRender<'f, F, M>
+where
+    F: Grid + ?Sized,
+    M: Fn(&F::Item) -> char,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (width, height) = (self.field.width(), self.field.height());
+
+        // Later overlays take precedence over earlier ones
+        let mut cells = Vec::new();
+        if !self.overlays.is_empty() {
+            cells.resize(width * height, (None, None));
+            for o in &self.overlays {
+                for &off in &o.offsets {
+                    let cell = &mut cells[off];
+                    cell.0 = o.ch.or(cell.0);
+                    cell.1 = o.color.or(cell.1);
+                }
+            }
+        }
+
+        for y in 0..height {
+            for x in 0..width {
+                let (ch, color) = cells.get(y * width + x).copied().unwrap_or_default();
+                let ch = ch.unwrap_or_else(|| (self.map)(self.field.cell(x, y)));
+                match color {
+                    Some(color) => write!(f, "\x1b[{}m{ch}\x1b[0m", color.ansi_code())?,
+                    None => f.write_char(ch)?,
+                }
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Visualizer {
+    enabled: bool,
+    waiting: bool,
+    skip: usize,
+    frame: usize,
+}
+
+impl Visualizer {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            waiting: true,
+            skip: 0,
+            frame: 0,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn frame_index(&self) -> usize {
+        self.frame
+    }
+
+    pub fn show<D: Display>(&mut self, f: impl FnOnce() -> D) {
+        if !self.enabled {
+            return;
+        }
+
+        let frame = self.frame;
+        self.frame += 1;
+        if self.skip > 0 {
+            self.skip -= 1;
+            return;
+        }
+
+        println!("\x1b[2J\x1b[H[frame {frame}]\n{}", f());
+
+        if !self.waiting {
+            return;
+        }
+
+        println!("<enter> next, <n> skip n frames, c continue, q quit visualizer");
+        let mut line = String::new();
+        // Without an interactive stdin there's nobody to step through the frames
+        if !matches!(std::io::stdin().lock().read_line(&mut line), Ok(n) if n > 0) {
+            self.enabled = false;
+            return;
+        }
+
+        match line.trim() {
+            "c" => self.waiting = false,
+            "q" => self.enabled = false,
+            n => self.skip = n.parse().unwrap_or(0),
+        }
+    }
+}
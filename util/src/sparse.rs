@@ -0,0 +1,352 @@
+use crate::{coord, Coord, FastHashMap, Field, FieldView, Grid};
+use std::{
+    fmt::{Display, Formatter, Write},
+    ops::{Index, IndexMut},
+};
+
+fn union_bounds(
+    bounds: Option<(Coord<i32>, Coord<i32>)>,
+    pos: Coord<i32>,
+) -> (Coord<i32>, Coord<i32>) {
+    match bounds {
+        None => (pos, pos),
+        Some((min, max)) => (min.component_min(pos), max.component_max(pos)),
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct SparseField<T> {
+    cells: FastHashMap<Coord<i32>, T>,
+}
+
+impl<T> SparseField<T> {
+    pub fn new() -> Self {
+        Self {
+            cells: FastHashMap::default(),
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            cells: FastHashMap::with_capacity_and_hasher(capacity, Default::default()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    pub fn contains(&self, pos: Coord<i32>) -> bool {
+        self.cells.contains_key(&pos)
+    }
+
+    pub fn get(&self, pos: Coord<i32>) -> Option<&T> {
+        self.cells.get(&pos)
+    }
+
+    pub fn get_or<'r>(&'r self, pos: Coord<i32>, alt: &'r T) -> &'r T {
+        self.cells.get(&pos).unwrap_or(alt)
+    }
+
+    pub fn get_mut(&mut self, pos: Coord<i32>) -> Option<&mut T> {
+        self.cells.get_mut(&pos)
+    }
+
+    pub fn get_or_insert_with(&mut self, pos: Coord<i32>, f: impl FnOnce() -> T) -> &mut T {
+        self.cells.entry(pos).or_insert_with(f)
+    }
+
+    pub fn insert(&mut self, pos: Coord<i32>, value: T) -> Option<T> {
+        self.cells.insert(pos, value)
+    }
+
+    pub fn remove(&mut self, pos: Coord<i32>) -> Option<T> {
+        self.cells.remove(&pos)
+    }
+
+    pub fn coords(&self) -> impl Iterator<Item = Coord<i32>> + '_ {
+        self.cells.keys().copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Coord<i32>, &T)> {
+        self.cells.iter().map(|(&c, v)| (c, v))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Coord<i32>, &mut T)> {
+        self.cells.iter_mut().map(|(&c, v)| (c, v))
+    }
+
+    pub fn bounds(&self) -> Option<(Coord<i32>, Coord<i32>)> {
+        self.cells
+            .keys()
+            .fold(None, |b, &c| Some(union_bounds(b, c)))
+    }
+
+    pub fn to_dense(&self, fill: T) -> InfiniteField<T>
+    where
+        T: Clone,
+    {
+        let mut r = InfiniteField::new(fill);
+        if let Some((min, max)) = self.bounds() {
+            r.reserve(min, max);
+        }
+        for (&c, v) in &self.cells {
+            r.set(c, v.clone());
+        }
+        r
+    }
+}
+
+impl<T> SparseField<T> {
+    // Draws the bounding box of the set cells, with `map` getting None for the unset ones
+    pub fn render_with<M: Fn(Option<&T>) -> char>(&self, map: M) -> SparseRender<'_, T, M> {
+        SparseRender { field: self, map }
+    }
+}
+
+impl SparseField<()> {
+    pub fn mark(&mut self, pos: Coord<i32>) -> bool {
+        self.cells.insert(pos, ()).is_none()
+    }
+}
+
+impl<T> Index<Coord<i32>> for SparseField<T> {
+    type Output = T;
+    fn index(&self, pos: Coord<i32>) -> &Self::Output {
+        &self.cells[&pos]
+    }
+}
+
+impl<T> FromIterator<(Coord<i32>, T)> for SparseField<T> {
+    fn from_iter<It: IntoIterator<Item = (Coord<i32>, T)>>(iter: It) -> Self {
+        Self {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+pub struct SparseRender<'f, T, M> {
+    field: &'f SparseField<T>,
+    map: M,
+}
+
+impl<T, M: Fn(Option<&T>) -> char> Display for SparseRender<'_, T, M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let Some((min, max)) = self.field.bounds() else {
+            return Ok(());
+        };
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                f.write_char((self.map)(self.field.get(coord(x, y))))?;
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct InfiniteField<T> {
+    data: Vec<T>,
+    origin: Coord<i32>,
+    alloc_width: usize,
+    alloc_height: usize,
+    used: Option<(Coord<i32>, Coord<i32>)>,
+    fill: T,
+}
+
+impl<T: Clone> InfiniteField<T> {
+    pub fn new(fill: T) -> Self {
+        Self {
+            data: Vec::new(),
+            origin: coord(0, 0),
+            alloc_width: 0,
+            alloc_height: 0,
+            used: None,
+            fill,
+        }
+    }
+
+    pub fn from_view(view: &FieldView<'_, T>, origin: Coord<i32>, fill: T) -> Self {
+        let mut r = Self::new(fill);
+        if view.width() > 0 && view.height() > 0 {
+            r.reserve(
+                origin,
+                origin + coord(view.width() as i32 - 1, view.height() as i32 - 1),
+            );
+            for (y, row) in view.rows().enumerate() {
+                let o = r.local_offset(origin + coord(0, y as i32));
+                r.data[o..o + row.len()].clone_from_slice(row);
+            }
+        }
+        r
+    }
+
+    fn local_offset(&self, pos: Coord<i32>) -> usize {
+        let l = pos - self.origin;
+        l.y as usize * self.alloc_width + l.x as usize
+    }
+
+    fn in_alloc(&self, pos: Coord<i32>) -> bool {
+        let l = pos - self.origin;
+        (l.x as usize) < self.alloc_width && (l.y as usize) < self.alloc_height
+    }
+
+    pub fn fill_value(&self) -> &T {
+        &self.fill
+    }
+
+    pub fn bounds(&self) -> Option<(Coord<i32>, Coord<i32>)> {
+        self.used
+    }
+
+    pub fn contains(&self, pos: Coord<i32>) -> bool {
+        self.used.is_some_and(|(min, max)| {
+            (min.x..=max.x).contains(&pos.x) && (min.y..=max.y).contains(&pos.y)
+        })
+    }
+
+    pub fn get(&self, pos: Coord<i32>) -> &T {
+        if self.in_alloc(pos) {
+            &self.data[self.local_offset(pos)]
+        } else {
+            &self.fill
+        }
+    }
+
+    pub fn get_mut(&mut self, pos: Coord<i32>) -> &mut T {
+        self.reserve(pos, pos);
+        self.used = Some(union_bounds(self.used, pos));
+        let o = self.local_offset(pos);
+        &mut self.data[o]
+    }
+
+    pub fn set(&mut self, pos: Coord<i32>, value: T) {
+        *self.get_mut(pos) = value;
+    }
+
+    pub fn reserve(&mut self, min: Coord<i32>, max: Coord<i32>) {
+        if self.in_alloc(min) && self.in_alloc(max) {
+            return;
+        }
+
+        let (old_min, old_max) = if self.alloc_width == 0 {
+            (min, max)
+        } else {
+            (
+                self.origin,
+                self.origin + coord(self.alloc_width as i32 - 1, self.alloc_height as i32 - 1),
+            )
+        };
+
+        // Grow by at least half the current size in every direction that needs to grow, so repeated
+        // writes just outside the edge don't reallocate every time
+        let slack = coord(self.alloc_width as i32 / 2, self.alloc_height as i32 / 2);
+        let grow_lo = |v: i32, old: i32, slack: i32| if v < old { v.min(old - slack) } else { old };
+        let grow_hi = |v: i32, old: i32, slack: i32| if v > old { v.max(old + slack) } else { old };
+        let new_min = coord(
+            grow_lo(min.x, old_min.x, slack.x),
+            grow_lo(min.y, old_min.y, slack.y),
+        );
+        let new_max = coord(
+            grow_hi(max.x, old_max.x, slack.x),
+            grow_hi(max.y, old_max.y, slack.y),
+        );
+
+        let width = (new_max.x - new_min.x + 1) as usize;
+        let height = (new_max.y - new_min.y + 1) as usize;
+        let mut data = vec![self.fill.clone(); width * height];
+        let shift = self.origin - new_min;
+        for (y, row) in self.data.chunks(self.alloc_width.max(1)).enumerate() {
+            let o = (y as i32 + shift.y) as usize * width + shift.x as usize;
+            data[o..o + row.len()].clone_from_slice(row);
+        }
+
+        self.data = data;
+        self.origin = new_min;
+        self.alloc_width = width;
+        self.alloc_height = height;
+    }
+
+    pub fn view(&self) -> FieldView<'_, T> {
+        match self.used {
+            None => FieldView::new(&self.data, 0, 0, 0),
+            Some((min, max)) => {
+                let (width, height) = ((max.x - min.x + 1) as usize, (max.y - min.y + 1) as usize);
+                let start = self.local_offset(min);
+                FieldView::new(
+                    &self.data[start..start + self.alloc_width * (height - 1) + width],
+                    width,
+                    self.alloc_width,
+                    height,
+                )
+            }
+        }
+    }
+
+    pub fn to_view_coord(&self, pos: Coord<i32>) -> Coord<i32> {
+        pos - self.used.map_or(coord(0, 0), |b| b.0)
+    }
+
+    pub fn coords(&self) -> impl Iterator<Item = Coord<i32>> + 'static {
+        let (min, max) = self.used.unwrap_or((coord(0, 0), coord(-1, -1)));
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| coord(x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Coord<i32>, &T)> {
+        self.coords().map(|c| (c, self.get(c)))
+    }
+}
+
+impl<T: Clone> Index<Coord<i32>> for InfiniteField<T> {
+    type Output = T;
+    fn index(&self, pos: Coord<i32>) -> &Self::Output {
+        self.get(pos)
+    }
+}
+
+impl<T: Clone> IndexMut<Coord<i32>> for InfiniteField<T> {
+    fn index_mut(&mut self, pos: Coord<i32>) -> &mut Self::Output {
+        self.get_mut(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sparse_render() {
+        let mut s = SparseField::new();
+        for c in [coord(-1, -1), coord(1, 0), coord(0, 1)] {
+            s.mark(c);
+        }
+        let r = s.render_with(|c| if c.is_some() { '#' } else { '.' });
+        assert_eq!(r.to_string(), "#..\n..#\n.#.\n");
+        assert_eq!(
+            SparseField::<()>::new().render_with(|_| '#').to_string(),
+            ""
+        );
+    }
+
+    #[test]
+    fn infinite_view_is_exact() {
+        let mut f = InfiniteField::new(0u8);
+        f.reserve(coord(-4, -4), coord(4, 4));
+        f.set(coord(-1, 0), 1);
+        f.set(coord(1, 1), 2);
+        let v = f.view();
+        assert_eq!((v.width(), v.height()), (3, 2));
+        assert_eq!(v.data().len(), v.stride() + 3);
+        assert_eq!(v.rows().collect::<Vec<_>>(), [[1, 0, 0], [0, 0, 2]]);
+        assert_eq!(v[f.to_view_coord(coord(1, 1))], 2);
+    }
+}
@@ -0,0 +1,388 @@
+const NONE: u32 = u32::MAX;
+
+// A set of byte strings stored as a trie. Every node is a small array with one slot per byte that
+// actually occurs in the words, so a four letter alphabet costs four slots per node, not 256.
+#[derive(Clone, Debug)]
+pub struct Trie {
+    symbols: [Option<u8>; 256],
+    width: usize,
+    next: Vec<u32>,
+    terminal: Vec<bool>,
+    words: usize,
+}
+
+impl Default for Trie {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Trie {
+    pub fn new() -> Self {
+        Self {
+            symbols: [None; 256],
+            width: 0,
+            next: Vec::new(),
+            terminal: vec![false],
+            words: 0,
+        }
+    }
+
+    pub fn from_words<W: AsRef<[u8]>>(words: impl IntoIterator<Item = W>) -> Self {
+        let mut trie = Self::new();
+        for w in words {
+            trie.insert(w);
+        }
+        trie
+    }
+
+    // The number of distinct bytes in all words
+    pub fn alphabet_len(&self) -> usize {
+        self.width
+    }
+
+    // The slot of a byte, widening every node when it's new
+    fn symbol(&mut self, b: u8) -> usize {
+        if let Some(s) = self.symbols[b as usize] {
+            return s as usize;
+        }
+        let s = self.width;
+        self.symbols[b as usize] = Some(s as u8);
+        self.width += 1;
+        let mut next = Vec::with_capacity(self.terminal.len() * self.width);
+        for node in 0..self.terminal.len() {
+            next.extend_from_slice(&self.next[node * s..(node + 1) * s]);
+            next.push(NONE);
+        }
+        self.next = next;
+        s
+    }
+
+    fn child(&self, node: usize, b: u8) -> Option<usize> {
+        let s = self.symbols[b as usize]? as usize;
+        let n = self.next[node * self.width + s];
+        (n != NONE).then_some(n as usize)
+    }
+
+    // Returns false if the word was already there
+    pub fn insert(&mut self, word: impl AsRef<[u8]>) -> bool {
+        let mut node = 0;
+        for &b in word.as_ref() {
+            let s = self.symbol(b);
+            let slot = node * self.width + s;
+            if self.next[slot] == NONE {
+                self.next[slot] = self.terminal.len() as u32;
+                self.terminal.push(false);
+                self.next.resize(self.terminal.len() * self.width, NONE);
+            }
+            node = self.next[slot] as usize;
+        }
+        let new = !self.terminal[node];
+        self.terminal[node] = true;
+        self.words += new as usize;
+        new
+    }
+
+    pub fn contains(&self, word: impl AsRef<[u8]>) -> bool {
+        let mut node = 0;
+        for &b in word.as_ref() {
+            match self.child(node, b) {
+                Some(n) => node = n,
+                None => return false,
+            }
+        }
+        self.terminal[node]
+    }
+
+    pub fn len(&self) -> usize {
+        self.words
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words == 0
+    }
+
+    // The lengths of all words that are a prefix of s, shortest first
+    pub fn prefix_lengths<'a>(&'a self, s: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        let mut node = Some(0);
+        let mut len = 0;
+        std::iter::from_fn(move || {
+            while let Some(n) = node {
+                let l = len;
+                node = s.get(len).and_then(|&b| self.child(n, b));
+                len += 1;
+                if self.terminal[n] {
+                    return Some(l);
+                }
+            }
+            None
+        })
+    }
+
+    // The number of ways to split s into a sequence of words. The empty word doesn't count as a
+    // piece, so this is always finite, but it grows exponentially with the length of s: a single
+    // letter and its double split 93 letters into more ways than fit into a u64. Returns None once
+    // the count reaches u64::MAX.
+    pub fn count_segmentations(&self, s: &[u8]) -> Option<u64> {
+        // Saturating, so a suffix that overflowed only matters if some split actually reaches it
+        let mut ways = vec![0_u64; s.len() + 1];
+        ways[s.len()] = 1;
+        for i in (0..s.len()).rev() {
+            ways[i] = self
+                .prefix_lengths(&s[i..])
+                .filter(|&l| l > 0)
+                .fold(0, |sum, l| sum.saturating_add(ways[i + l]));
+        }
+        (ways[0] != u64::MAX).then_some(ways[0])
+    }
+
+    pub fn can_segment(&self, s: &[u8]) -> bool {
+        let mut possible = vec![false; s.len() + 1];
+        possible[s.len()] = true;
+        for i in (0..s.len()).rev() {
+            possible[i] = self
+                .prefix_lengths(&s[i..])
+                .any(|l| l > 0 && possible[i + l]);
+        }
+        possible[0]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WordMatch {
+    pub pattern: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+// Finds all occurrences of many patterns in a single pass. The automaton is a complete DFA, so every
+// byte of the haystack is a single table lookup, with bytes outside the alphabet sharing column 0.
+// Symbols are u16 so that column stays reserved even when the patterns use all 256 byte values.
+#[derive(Clone, Debug)]
+pub struct AhoCorasick {
+    symbols: [u16; 256],
+    width: usize,
+    delta: Vec<u32>,
+    // Patterns that end exactly at a state
+    outputs: Vec<Vec<usize>>,
+    // The next state along the failure chain that has outputs
+    dict: Vec<u32>,
+    lens: Vec<usize>,
+}
+
+impl AhoCorasick {
+    pub fn new<P: AsRef<[u8]>>(patterns: impl IntoIterator<Item = P>) -> Self {
+        let patterns: Vec<P> = patterns.into_iter().collect();
+        let mut symbols = [0_u16; 256];
+        let mut width = 1;
+        for p in &patterns {
+            for &b in p.as_ref() {
+                if symbols[b as usize] == 0 {
+                    symbols[b as usize] = width as u16;
+                    width += 1;
+                }
+            }
+        }
+        let mut delta = vec![NONE; width];
+        let mut outputs = vec![Vec::new()];
+        for (id, p) in patterns.iter().enumerate() {
+            let mut state = 0;
+            for &b in p.as_ref() {
+                let slot = state * width + symbols[b as usize] as usize;
+                if delta[slot] == NONE {
+                    delta[slot] = outputs.len() as u32;
+                    outputs.push(Vec::new());
+                    delta.resize(outputs.len() * width, NONE);
+                }
+                state = delta[slot] as usize;
+            }
+            if state != 0 {
+                outputs[state].push(id);
+            }
+        }
+
+        // Breadth first, so the failure state of every state is complete before it is needed
+        let mut fail = vec![0_u32; outputs.len()];
+        let mut dict = vec![NONE; outputs.len()];
+        let mut queue = std::collections::VecDeque::new();
+        for slot in &mut delta[..width] {
+            match *slot {
+                NONE => *slot = 0,
+                child => queue.push_back(child as usize),
+            }
+        }
+        while let Some(s) = queue.pop_front() {
+            let f = fail[s] as usize;
+            dict[s] = if outputs[f].is_empty() {
+                dict[f]
+            } else {
+                f as u32
+            };
+            for c in 0..width {
+                let t = delta[s * width + c];
+                if t == NONE {
+                    delta[s * width + c] = delta[f * width + c];
+                } else {
+                    fail[t as usize] = delta[f * width + c];
+                    queue.push_back(t as usize);
+                }
+            }
+        }
+
+        Self {
+            symbols,
+            width,
+            delta,
+            outputs,
+            dict,
+            lens: patterns.iter().map(|p| p.as_ref().len()).collect(),
+        }
+    }
+
+    pub fn pattern_count(&self) -> usize {
+        self.lens.len()
+    }
+
+    // All matches including overlapping ones, ordered by end position. Empty patterns are ignored.
+    pub fn find_iter<'a>(&'a self, haystack: &'a [u8]) -> impl Iterator<Item = WordMatch> + 'a {
+        let mut pos = 0;
+        let mut state = 0;
+        let mut out_state = NONE;
+        let mut k = 0;
+        std::iter::from_fn(move || loop {
+            if out_state != NONE {
+                if let Some(&pattern) = self.outputs[out_state as usize].get(k) {
+                    k += 1;
+                    return Some(WordMatch {
+                        pattern,
+                        start: pos - self.lens[pattern],
+                        end: pos,
+                    });
+                }
+                out_state = self.dict[out_state as usize];
+                k = 0;
+                continue;
+            }
+            let &b = haystack.get(pos)?;
+            state = self.delta[state * self.width + self.symbols[b as usize] as usize] as usize;
+            pos += 1;
+            out_state = state as u32;
+            k = 0;
+        })
+    }
+
+    pub fn is_match(&self, haystack: &[u8]) -> bool {
+        self.find_iter(haystack).next().is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn widening_keeps_the_words() {
+        let mut t = Trie::from_words(["ab", "b"]);
+        assert_eq!(t.alphabet_len(), 2);
+        // Every new byte widens all existing nodes
+        assert!(t.insert("abc"));
+        assert!(t.insert("ca"));
+        assert!(!t.insert("ab"));
+        assert_eq!((t.alphabet_len(), t.len()), (3, 4));
+        for w in ["ab", "b", "abc", "ca"] {
+            assert!(t.contains(w), "{w}");
+        }
+        for w in ["", "a", "c", "abcd", "bc", "z"] {
+            assert!(!t.contains(w), "{w}");
+        }
+        assert!(t.insert(""));
+        assert!(t.contains("") && t.len() == 5);
+
+        let all: Vec<u8> = (0..=255).collect();
+        let t = Trie::from_words([&all[..], &all[255..]]);
+        assert_eq!(t.alphabet_len(), 256);
+        assert!(t.contains(&all) && t.contains([255]) && !t.contains([0]));
+    }
+
+    #[test]
+    fn prefix_lengths() {
+        let t = Trie::from_words(["", "a", "abc", "abcd", "b"]);
+        let p = |s: &str| t.prefix_lengths(s.as_bytes()).collect::<Vec<_>>();
+        assert_eq!(p("abcde"), [0, 1, 3, 4]);
+        assert_eq!(p("abc"), [0, 1, 3]);
+        assert_eq!(p("ab"), [0, 1]);
+        assert_eq!(p("x"), [0]);
+        assert_eq!(p(""), [0]);
+        assert_eq!(Trie::new().prefix_lengths(b"a").count(), 0);
+    }
+
+    #[test]
+    fn segmentations() {
+        let t = Trie::from_words(["r", "wr", "b", "g", "bwu", "rb", "gb", "br"]);
+        assert_eq!(t.count_segmentations(b"brwrr"), Some(2));
+        assert_eq!(t.count_segmentations(b"gbbr"), Some(4));
+        assert_eq!(t.count_segmentations(b"ubwu"), Some(0));
+        assert_eq!(t.count_segmentations(b""), Some(1));
+        assert!(t.can_segment(b"rrbgbr") && !t.can_segment(b"bbrgwb"));
+
+        // n letters split into a and aa in F(n + 1) ways, and F(94) is past u64::MAX
+        let t = Trie::from_words(["a", "aa"]);
+        assert_eq!(
+            t.count_segmentations(&[b'a'; 92]),
+            Some(12_200_160_415_121_876_738)
+        );
+        assert_eq!(t.count_segmentations(&[b'a'; 93]), None);
+        // A suffix that overflows doesn't matter if nothing reaches it
+        let mut s = vec![b'b'];
+        s.extend([b'a'; 100]);
+        assert_eq!(t.count_segmentations(&s), Some(0));
+    }
+
+    fn naive(patterns: &[&[u8]], haystack: &[u8]) -> Vec<WordMatch> {
+        let mut r = Vec::new();
+        for end in 1..=haystack.len() {
+            for (pattern, p) in patterns.iter().enumerate() {
+                if !p.is_empty() && haystack[..end].ends_with(p) {
+                    r.push(WordMatch {
+                        pattern,
+                        start: end - p.len(),
+                        end,
+                    });
+                }
+            }
+        }
+        r
+    }
+
+    fn sorted(mut v: Vec<WordMatch>) -> Vec<WordMatch> {
+        v.sort_unstable_by_key(|m| (m.end, m.pattern));
+        v
+    }
+
+    #[test]
+    fn overlapping_matches() {
+        let patterns: [&[u8]; 5] = [b"he", b"she", b"his", b"hers", b""];
+        let ac = AhoCorasick::new(patterns);
+        let found = sorted(ac.find_iter(b"ushers").collect());
+        assert_eq!(found, naive(&patterns, b"ushers"));
+        let found: Vec<_> = found.iter().map(|m| (m.pattern, m.start, m.end)).collect();
+        assert_eq!(found, [(0, 2, 4), (1, 1, 4), (3, 2, 6)]);
+        assert!(!ac.is_match(b"xyz"));
+    }
+
+    #[test]
+    fn all_byte_values() {
+        // Every byte value shows up in some pattern, so no column is left for unknown bytes
+        let bytes: Vec<u8> = (0..=255).collect();
+        let patterns: Vec<&[u8]> = vec![&bytes[..128], &bytes[128..], &[255, 0], &[0], &[255]];
+        let ac = AhoCorasick::new(&patterns);
+        let haystack: Vec<u8> = bytes.iter().chain(&bytes).copied().collect();
+        let found = sorted(ac.find_iter(&haystack).collect());
+        assert_eq!(found, naive(&patterns, &haystack));
+        assert!(found.contains(&WordMatch {
+            pattern: 2,
+            start: 255,
+            end: 257
+        }));
+    }
+}
@@ -0,0 +1,217 @@
+use crate::{
+    coord3, Coord3, Decrement, FieldMutView, FieldView, FromPrimitive, Increment, PrimitiveInt,
+    ToPrimitive,
+};
+use std::ops::{Index, IndexMut};
+
+// A dense 3D grid, stored as `depth` layers of `height` rows of `width` cells. Every layer can be
+// looked at as a regular 2D field.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Volume<T> {
+    data: Vec<T>,
+    width: usize,
+    height: usize,
+    depth: usize,
+}
+
+impl<T> Volume<T> {
+    pub fn new(width: usize, height: usize, depth: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            data: vec![fill; width * height * depth],
+            width,
+            height,
+            depth,
+        }
+    }
+
+    pub fn from_fn<I: FromPrimitive<usize>>(
+        width: usize,
+        height: usize,
+        depth: usize,
+        mut f: impl FnMut(Coord3<I>) -> T,
+    ) -> Self {
+        let data = (0..width * height * depth)
+            .map(|o| {
+                f(coord3(
+                    I::from(o % width),
+                    I::from(o / width % height),
+                    I::from(o / width / height),
+                ))
+            })
+            .collect();
+        Self {
+            data,
+            width,
+            height,
+            depth,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn data(&self) -> &[T] {
+        &self.data
+    }
+
+    pub fn data_mut(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+
+    pub fn offset<I: PrimitiveInt + ToPrimitive<usize>>(&self, p: Coord3<I>) -> usize {
+        (p.z.to() * self.height + p.y.to()) * self.width + p.x.to()
+    }
+
+    pub fn coord_from_offset<I: FromPrimitive<usize>>(&self, o: usize) -> Coord3<I> {
+        coord3(
+            I::from(o % self.width),
+            I::from(o / self.width % self.height),
+            I::from(o / self.width / self.height),
+        )
+    }
+
+    pub fn in_bounds<I: PrimitiveInt + ToPrimitive<usize>>(&self, p: Coord3<I>) -> bool {
+        in_dims(p, (self.width, self.height, self.depth))
+    }
+
+    pub fn get<I: PrimitiveInt + ToPrimitive<usize>>(&self, p: Coord3<I>) -> Option<&T> {
+        self.in_bounds(p).then(|| &self.data[self.offset(p)])
+    }
+
+    pub fn get_mut<I: PrimitiveInt + ToPrimitive<usize>>(
+        &mut self,
+        p: Coord3<I>,
+    ) -> Option<&mut T> {
+        if self.in_bounds(p) {
+            let o = self.offset(p);
+            Some(&mut self.data[o])
+        } else {
+            None
+        }
+    }
+
+    pub fn layer(&self, z: usize) -> FieldView<'_, T> {
+        let size = self.width * self.height;
+        FieldView::new(
+            &self.data[z * size..(z + 1) * size],
+            self.width,
+            self.width,
+            self.height,
+        )
+    }
+
+    pub fn layer_mut(&mut self, z: usize) -> FieldMutView<'_, T> {
+        let size = self.width * self.height;
+        FieldMutView::from_ref(
+            &mut self.data[z * size..(z + 1) * size],
+            self.width,
+            self.width,
+            self.height,
+        )
+    }
+
+    pub fn coords<I: FromPrimitive<usize>>(&self) -> impl Iterator<Item = Coord3<I>> + 'static {
+        let (w, h, d) = (self.width, self.height, self.depth);
+        (0..d).flat_map(move |z| {
+            (0..h)
+                .flat_map(move |y| (0..w).map(move |x| coord3(I::from(x), I::from(y), I::from(z))))
+        })
+    }
+
+    pub fn neighbors6<I>(&self, p: Coord3<I>) -> impl Iterator<Item = Coord3<I>>
+    where
+        I: PrimitiveInt + ToPrimitive<usize> + Increment + Decrement,
+    {
+        let (w, h, d) = (self.width, self.height, self.depth);
+        p.neighbors6()
+            .into_iter()
+            .filter(move |&n| in_dims(n, (w, h, d)))
+    }
+}
+
+fn in_dims<I: PrimitiveInt + ToPrimitive<usize>>(
+    p: Coord3<I>,
+    (w, h, d): (usize, usize, usize),
+) -> bool {
+    let fits = |v: I, len: usize| v.try_to().is_some_and(|v: usize| v < len);
+    fits(p.x, w) && fits(p.y, h) && fits(p.z, d)
+}
+
+impl<T, I: PrimitiveInt + ToPrimitive<usize>> Index<Coord3<I>> for Volume<T> {
+    type Output = T;
+    fn index(&self, pos: Coord3<I>) -> &Self::Output {
+        &self.data[self.offset(pos)]
+    }
+}
+
+impl<T, I: PrimitiveInt + ToPrimitive<usize>> IndexMut<Coord3<I>> for Volume<T> {
+    fn index_mut(&mut self, pos: Coord3<I>) -> &mut Self::Output {
+        let o = self.offset(pos);
+        &mut self.data[o]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Grid;
+
+    #[test]
+    fn indexing() {
+        let mut v = Volume::from_fn(4, 3, 2, |p: Coord3<i32>| p.x + 10 * p.y + 100 * p.z);
+        assert_eq!(v.data().len(), 24);
+        for (o, p) in v.coords::<i32>().enumerate() {
+            assert_eq!(v.offset(p), o);
+            assert_eq!(v.coord_from_offset::<i32>(o), p);
+            assert_eq!(v[p], v.data()[o]);
+        }
+        assert_eq!(v[coord3(3, 2, 1)], 123);
+        v[coord3(1, 1, 1)] = -1;
+        *v.get_mut(coord3(2, 0, 0)).unwrap() = -2;
+        assert_eq!(v.data()[v.offset(coord3(1, 1, 1))], -1);
+
+        let layer = v.layer(1);
+        assert_eq!((layer.width(), layer.height()), (4, 3));
+        assert_eq!(*layer.cell(3, 2), 123);
+        assert_eq!(*layer.cell(1, 1), -1);
+        assert_eq!(*v.layer(0).cell(2, 0), -2);
+    }
+
+    #[test]
+    fn bounds() {
+        let v = Volume::new(4, 3, 2, 0_u8);
+        assert!(v.in_bounds(coord3(3, 2, 1)));
+        for p in [
+            coord3(4, 0, 0),
+            coord3(0, 3, 0),
+            coord3(0, 0, 2),
+            coord3(-1, 0, 0),
+            coord3(0, 0, -1),
+        ] {
+            assert!(!v.in_bounds(p));
+            assert_eq!(v.get(p), None);
+        }
+
+        let corner: Vec<_> = v.neighbors6(coord3(0, 0, 0)).collect();
+        assert_eq!(corner, [coord3(1, 0, 0), coord3(0, 1, 0), coord3(0, 0, 1)]);
+        assert_eq!(v.neighbors6(coord3(1_i64, 1, 1)).count(), 5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn layer_out_of_range() {
+        Volume::new(2, 2, 2, 0).layer(2);
+    }
+}
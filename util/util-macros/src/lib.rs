@@ -1,4 +1,4 @@
-use proc_macro::{TokenStream, TokenTree};
+use proc_macro::{Delimiter, TokenStream, TokenTree};
 use quote::*;
 
 #[proc_macro_attribute]
@@ -33,3 +33,110 @@ pub fn aoc_day(_params: TokenStream, mut input: TokenStream) -> TokenStream {
     input.extend(TokenStream::from(main));
     input
 }
+
+#[proc_macro_derive(Cell, attributes(cell))]
+pub fn derive_cell(input: TokenStream) -> TokenStream {
+    match derive_cell_impl(input) {
+        Ok(ts) => ts,
+        Err(msg) => quote! { compile_error!(#msg); }.into(),
+    }
+}
+
+fn derive_cell_impl(input: TokenStream) -> Result<TokenStream, String> {
+    let mut it = input.into_iter();
+    let name = 'name: {
+        while let Some(item) = it.next() {
+            if let TokenTree::Ident(ident) = item {
+                if ident.to_string() == "enum" {
+                    if let Some(TokenTree::Ident(name)) = it.next() {
+                        break 'name name;
+                    }
+                }
+            }
+        }
+        return Err("#[derive(Cell)] is only supported on enums".into());
+    };
+
+    let body = it
+        .find_map(|t| match t {
+            TokenTree::Group(g) if g.delimiter() == Delimiter::Brace => Some(g),
+            _ => None,
+        })
+        .ok_or("Couldn't find enum body")?;
+
+    let mut variants = Vec::new();
+    let mut cells = Vec::new();
+    let mut tokens = body.stream().into_iter().peekable();
+    while let Some(t) = tokens.next() {
+        match t {
+            TokenTree::Punct(p) if p.as_char() == '#' => {
+                let Some(TokenTree::Group(attr)) = tokens.next() else {
+                    return Err("Malformed attribute".into());
+                };
+                let mut attr = attr.stream().into_iter();
+                if !matches!(attr.next(), Some(TokenTree::Ident(i)) if i.to_string() == "cell") {
+                    continue;
+                }
+                let Some(TokenTree::Group(args)) = attr.next() else {
+                    return Err("Expected #[cell(<char>, ...)]".into());
+                };
+                for arg in args.stream() {
+                    match arg {
+                        TokenTree::Literal(l) => cells.push(proc_macro2::TokenStream::from(
+                            TokenStream::from(TokenTree::Literal(l)),
+                        )),
+                        TokenTree::Punct(p) if p.as_char() == ',' => {}
+                        _ => return Err("Expected #[cell(<char>, ...)]".into()),
+                    }
+                }
+            }
+            TokenTree::Ident(ident) => {
+                let variant = proc_macro2::Ident::new(&ident.to_string(), ident.span().into());
+                if cells.is_empty() {
+                    return Err(format!(
+                        "Variant `{ident}` is missing a #[cell(...)] attribute"
+                    ));
+                }
+                if matches!(tokens.peek(), Some(TokenTree::Group(_))) {
+                    return Err(format!("Variant `{ident}` must be a unit variant"));
+                }
+                // Skip an optional discriminant
+                for t in tokens.by_ref() {
+                    if matches!(t, TokenTree::Punct(ref p) if p.as_char() == ',') {
+                        break;
+                    }
+                }
+                variants.push((variant, std::mem::take(&mut cells)));
+            }
+            _ => {}
+        }
+    }
+
+    let name = proc_macro2::Ident::new(&name.to_string(), name.span().into());
+    let decode = variants.iter().map(|(v, cells)| {
+        quote! { #( if b == (#cells as u8) { return Ok(#name::#v); } )* }
+    });
+    let encode = variants.iter().map(|(v, cells)| {
+        let first = &cells[0];
+        quote! { #name::#v => #first as u8, }
+    });
+
+    Ok(quote! {
+        impl ::std::convert::TryFrom<u8> for #name {
+            type Error = u8;
+            fn try_from(b: u8) -> ::std::result::Result<Self, u8> {
+                #(#decode)*
+                Err(b)
+            }
+        }
+
+        impl ::std::convert::From<#name> for u8 {
+            fn from(c: #name) -> u8 {
+                match c {
+                    #(#encode)*
+                }
+            }
+        }
+    }
+    .into())
+}